@@ -0,0 +1,715 @@
+use std::time::Duration;
+
+use libpostal_api_models::{
+    ApiResponse, BatchNormalizeItem, BatchNormalizeRequest, BatchNormalizeResponse, BatchParseItem,
+    BatchParseRequest, BatchParseResponse, ErrorCode, ErrorResponse, FormatRequest,
+    FormattedAddressResponse, HealthResponse, MatchRequest, MatchResponse, NormalizeRequest,
+    NormalizedAddressResponse, ParseRequest, ParsedAddressResponse, ProblemDetails,
+};
+use reqwest::{header, Method, RequestBuilder, Response, StatusCode, Url};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::error::{Error, Result};
+
+/// Items sent per batch request, matching the server's default limit
+const DEFAULT_BATCH_SIZE: usize = 1000;
+
+/// Async client for the LibPostal API
+///
+/// Requests answered with 429, or with a 503 `QUEUE_FULL` or
+/// `SERVICE_UNAVAILABLE` carrying `Retry-After`, are retried, waiting as long
+/// as `Retry-After` asks or backing off exponentially without one.
+#[derive(Debug, Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: Url,
+    api_key: Option<String>,
+    retry: RetryPolicy,
+    batch_size: usize,
+}
+
+/// How requests turned away for load are retried
+#[derive(Debug, Clone, Copy)]
+struct RetryPolicy {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Wait before retry number `attempt`, starting from zero
+    fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        retry_after
+            .unwrap_or_else(|| {
+                self.initial_backoff
+                    .saturating_mul(2u32.saturating_pow(attempt))
+            })
+            .min(self.max_delay)
+    }
+}
+
+/// Builder for a [`Client`] with non-default settings
+#[derive(Debug)]
+pub struct ClientBuilder {
+    base_url: String,
+    api_key: Option<String>,
+    retry: RetryPolicy,
+    batch_size: usize,
+    timeout: Option<Duration>,
+    http: Option<reqwest::Client>,
+}
+
+impl ClientBuilder {
+    /// Send this API key with every request
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Retries after a rate limit or an overloaded server before giving up (default 3)
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.retry.max_retries = max_retries;
+        self
+    }
+
+    /// Wait before the first retry when the server gives no `Retry-After`,
+    /// doubled for each retry after that (default 500 ms)
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.retry.initial_backoff = backoff;
+        self
+    }
+
+    /// Longest wait between retries, whatever `Retry-After` asks (default 30 s)
+    pub fn max_retry_delay(mut self, delay: Duration) -> Self {
+        self.retry.max_delay = delay;
+        self
+    }
+
+    /// Items sent per request by the `*_all` batch helpers (default 1000)
+    ///
+    /// Keep it within the server's `max_batch_size`.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Timeout of each request, including each retry
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Send requests with this HTTP client, for custom TLS or proxy settings
+    ///
+    /// Takes precedence over [`Self::timeout`].
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    pub fn build(self) -> Result<Client> {
+        // A trailing slash makes relative endpoint paths join below the base path
+        let base = format!("{}/", self.base_url.trim_end_matches('/'));
+        let base_url = Url::parse(&base).map_err(|e| Error::InvalidUrl(format!("{base}: {e}")))?;
+        if base_url.cannot_be_a_base() {
+            return Err(Error::InvalidUrl(format!("{base}: not a base URL")));
+        }
+
+        let http = match self.http {
+            Some(http) => http,
+            None => {
+                let mut builder = reqwest::Client::builder();
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                builder.build()?
+            }
+        };
+
+        Ok(Client {
+            http,
+            base_url,
+            api_key: self.api_key,
+            retry: self.retry,
+            batch_size: self.batch_size,
+        })
+    }
+}
+
+impl Client {
+    /// Client for the API at `base_url`, such as `http://localhost:3000/api/v1`
+    pub fn new(base_url: impl Into<String>) -> Result<Self> {
+        Self::builder(base_url).build()
+    }
+
+    /// Builder for a client of the API at `base_url`
+    pub fn builder(base_url: impl Into<String>) -> ClientBuilder {
+        ClientBuilder {
+            base_url: base_url.into(),
+            api_key: None,
+            retry: RetryPolicy::default(),
+            batch_size: DEFAULT_BATCH_SIZE,
+            timeout: None,
+            http: None,
+        }
+    }
+
+    /// Parse an address into components
+    pub async fn parse(
+        &self,
+        request: &ParseRequest,
+    ) -> Result<ApiResponse<ParsedAddressResponse>> {
+        self.post("parse", request).await
+    }
+
+    /// Parse an address without hints
+    pub async fn parse_address(&self, address: &str) -> Result<ParsedAddressResponse> {
+        let request = ParseRequest {
+            address: address.to_string(),
+            ..ParseRequest::default()
+        };
+        Ok(self.parse(&request).await?.data)
+    }
+
+    /// Expand an address into its normalized forms
+    pub async fn normalize(
+        &self,
+        request: &NormalizeRequest,
+    ) -> Result<ApiResponse<NormalizedAddressResponse>> {
+        self.post("normalize", request).await
+    }
+
+    /// Render an address as a postal address for a country
+    pub async fn format(
+        &self,
+        request: &FormatRequest,
+    ) -> Result<ApiResponse<FormattedAddressResponse>> {
+        self.post("format", request).await
+    }
+
+    /// Compare addresses pairwise for duplicates
+    pub async fn match_addresses(
+        &self,
+        request: &MatchRequest,
+    ) -> Result<ApiResponse<MatchResponse>> {
+        self.post("match", request).await
+    }
+
+    /// Check that the service can take requests
+    pub async fn health(&self) -> Result<ApiResponse<HealthResponse>> {
+        self.send(Method::GET, "health", None::<&()>).await
+    }
+
+    /// Parse one batch; it must fit within the server's batch size limit
+    pub async fn parse_batch(
+        &self,
+        request: &BatchParseRequest,
+    ) -> Result<ApiResponse<BatchParseResponse>> {
+        self.post("parse/batch", request).await
+    }
+
+    /// Normalize one batch; it must fit within the server's batch size limit
+    pub async fn normalize_batch(
+        &self,
+        request: &BatchNormalizeRequest,
+    ) -> Result<ApiResponse<BatchNormalizeResponse>> {
+        self.post("normalize/batch", request).await
+    }
+
+    /// Parse any number of items, sending them in batches of the configured size
+    ///
+    /// Results come back in input order, with `index` counting from the first
+    /// item across all batches. Items that fail are reported in their results;
+    /// an error is only returned if a whole batch request fails.
+    pub async fn parse_all(
+        &self,
+        items: impl IntoIterator<Item = BatchParseItem>,
+    ) -> Result<BatchParseResponse> {
+        let mut combined = BatchParseResponse {
+            results: Vec::new(),
+            total: 0,
+            succeeded: 0,
+            failed: 0,
+        };
+
+        for chunk in chunks(items, self.batch_size) {
+            let offset = combined.results.len();
+            let response = self
+                .parse_batch(&BatchParseRequest { items: chunk })
+                .await?
+                .data;
+
+            combined.total += response.total;
+            combined.succeeded += response.succeeded;
+            combined.failed += response.failed;
+            combined
+                .results
+                .extend(response.results.into_iter().map(|mut result| {
+                    result.index += offset;
+                    result
+                }));
+        }
+
+        Ok(combined)
+    }
+
+    /// Normalize any number of items, sending them in batches of the configured size
+    ///
+    /// `level` and `languages` apply to every item that does not set its own.
+    /// Results are combined as by [`Self::parse_all`].
+    pub async fn normalize_all(
+        &self,
+        items: impl IntoIterator<Item = BatchNormalizeItem>,
+        level: Option<&str>,
+        languages: Option<&[String]>,
+    ) -> Result<BatchNormalizeResponse> {
+        let mut combined = BatchNormalizeResponse {
+            results: Vec::new(),
+            total: 0,
+            succeeded: 0,
+            failed: 0,
+        };
+
+        for chunk in chunks(items, self.batch_size) {
+            let offset = combined.results.len();
+            let request = BatchNormalizeRequest {
+                items: chunk,
+                level: level.map(str::to_string),
+                languages: languages.map(<[String]>::to_vec),
+            };
+            let response = self.normalize_batch(&request).await?.data;
+
+            combined.total += response.total;
+            combined.succeeded += response.succeeded;
+            combined.failed += response.failed;
+            combined
+                .results
+                .extend(response.results.into_iter().map(|mut result| {
+                    result.index += offset;
+                    result
+                }));
+        }
+
+        Ok(combined)
+    }
+
+    async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
+        self.send(Method::POST, path, Some(body)).await
+    }
+
+    /// Send a request, retrying while the server is rate limiting or overloaded
+    async fn send<B: Serialize, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T> {
+        let url = self
+            .base_url
+            .join(path)
+            .map_err(|e| Error::InvalidUrl(format!("{path}: {e}")))?;
+
+        let mut attempt = 0;
+        loop {
+            let response = self
+                .request(method.clone(), url.clone(), body)
+                .send()
+                .await?;
+            let status = response.status();
+            if status.is_success() {
+                return decode(response).await;
+            }
+
+            let error = error_from_response(response).await;
+            if !retryable(&error) || attempt >= self.retry.max_retries {
+                return Err(error);
+            }
+
+            let retry_after = match &error {
+                Error::Api { retry_after, .. } => *retry_after,
+                _ => None,
+            };
+            tokio::time::sleep(self.retry.delay(attempt, retry_after)).await;
+            attempt += 1;
+        }
+    }
+
+    fn request<B: Serialize>(&self, method: Method, url: Url, body: Option<&B>) -> RequestBuilder {
+        let mut request = self.http.request(method, url);
+        if let Some(api_key) = &self.api_key {
+            request = request.header("x-api-key", api_key);
+        }
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        request
+    }
+}
+
+/// Whether a failed request is worth sending again
+///
+/// Rate limits are always retried. A `503` is only retried when the server is
+/// overloaded and said when to come back; a missing model, unusable data or
+/// maintenance mode will not clear up within a few retries.
+fn retryable(error: &Error) -> bool {
+    match error {
+        Error::Api {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            code: ErrorCode::QueueFull | ErrorCode::ServiceUnavailable,
+            retry_after: Some(_),
+            ..
+        } => true,
+        error => error.status() == Some(StatusCode::TOO_MANY_REQUESTS),
+    }
+}
+
+/// Split items into batches of at most `size`
+fn chunks<T>(items: impl IntoIterator<Item = T>, size: usize) -> Vec<Vec<T>> {
+    let mut chunks = Vec::new();
+    let mut current = Vec::with_capacity(size);
+    for item in items {
+        current.push(item);
+        if current.len() == size {
+            chunks.push(std::mem::replace(&mut current, Vec::with_capacity(size)));
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+async fn decode<T: DeserializeOwned>(response: Response) -> Result<T> {
+    let status = response.status();
+    let body = response.bytes().await?;
+    serde_json::from_slice(&body).map_err(|e| Error::UnexpectedResponse {
+        status,
+        message: e.to_string(),
+    })
+}
+
+/// Turn an error response into an [`Error::Api`], if it has one of the API's error bodies
+async fn error_from_response(response: Response) -> Error {
+    let status = response.status();
+    let retry_after = response
+        .headers()
+        .get(header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .map(Duration::from_secs);
+
+    let body = match response.bytes().await {
+        Ok(body) => body,
+        Err(e) => return Error::Http(e),
+    };
+
+    if let Ok(error) = serde_json::from_slice::<ErrorResponse>(&body) {
+        return Error::Api {
+            status,
+            code: error.error.code,
+            message: error.error.message,
+            errors: Vec::new(),
+            retry_after,
+        };
+    }
+
+    match serde_json::from_slice::<ProblemDetails>(&body) {
+        Ok(problem) => Error::Api {
+            status,
+            code: problem.code,
+            message: problem.detail,
+            errors: problem.errors,
+            retry_after,
+        },
+        Err(_) => Error::UnexpectedResponse {
+            status,
+            message: String::from_utf8_lossy(&body).chars().take(200).collect(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use axum::{
+        extract::State,
+        http::{HeaderMap, StatusCode},
+        response::{IntoResponse, Response},
+        routing::post,
+        Json, Router,
+    };
+    use libpostal_api_models::{
+        AppliedHints, BatchParseItemResult, ErrorCode, ErrorDetail, FieldError, FieldErrorCode,
+        ParseRequest, PROBLEM_JSON,
+    };
+
+    use super::*;
+
+    type Hits = Arc<AtomicUsize>;
+
+    /// Serve `app` on a free local port and return a client for it
+    async fn serve(app: Router, configure: impl FnOnce(ClientBuilder) -> ClientBuilder) -> Client {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        configure(Client::builder(format!("http://{addr}/api/v1")).initial_backoff(Duration::ZERO))
+            .build()
+            .unwrap()
+    }
+
+    fn error(status: StatusCode, code: ErrorCode, retry_after: Option<&str>) -> Response {
+        let body = ErrorResponse {
+            success: false,
+            error: ErrorDetail {
+                code,
+                message: format!("{} happened", code.as_str()),
+                timestamp: String::new(),
+            },
+        };
+        let mut headers = HeaderMap::new();
+        if let Some(secs) = retry_after {
+            headers.insert(header::RETRY_AFTER, secs.parse().unwrap());
+        }
+        (status, headers, Json(body)).into_response()
+    }
+
+    fn parsed(address: &str) -> ParsedAddressResponse {
+        ParsedAddressResponse {
+            original: address.to_string(),
+            components: Default::default(),
+            hints: AppliedHints::default(),
+            tokens: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_after_rate_limit() {
+        async fn handler(State(hits): State<Hits>, Json(req): Json<ParseRequest>) -> Response {
+            if hits.fetch_add(1, Ordering::SeqCst) == 0 {
+                return error(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    ErrorCode::RateLimitExceeded,
+                    Some("0"),
+                );
+            }
+            Json(ApiResponse::new(parsed(&req.address), "req".into(), 0)).into_response()
+        }
+
+        let hits = Hits::default();
+        let app = Router::new()
+            .route("/api/v1/parse", post(handler))
+            .with_state(hits.clone());
+        let client = serve(app, |b| b).await;
+
+        let response = client.parse_address("1 Main St").await.unwrap();
+        assert_eq!(response.original, "1 Main St");
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn decodes_error_without_retrying() {
+        async fn handler(State(hits): State<Hits>) -> Response {
+            hits.fetch_add(1, Ordering::SeqCst);
+            error(StatusCode::BAD_REQUEST, ErrorCode::InvalidInput, None)
+        }
+
+        let hits = Hits::default();
+        let app = Router::new()
+            .route("/api/v1/parse", post(handler))
+            .with_state(hits.clone());
+        let client = serve(app, |b| b).await;
+
+        let err = client.parse_address("").await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+        assert_eq!(err.code(), Some(ErrorCode::InvalidInput));
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        async fn handler(State(hits): State<Hits>) -> Response {
+            hits.fetch_add(1, Ordering::SeqCst);
+            error(
+                StatusCode::SERVICE_UNAVAILABLE,
+                ErrorCode::QueueFull,
+                Some("0"),
+            )
+        }
+
+        let hits = Hits::default();
+        let app = Router::new()
+            .route("/api/v1/parse", post(handler))
+            .with_state(hits.clone());
+        let client = serve(app, |b| b.max_retries(2)).await;
+
+        let err = client.parse_address("1 Main St").await.unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::QueueFull));
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn only_retries_503s_for_load_with_retry_after() {
+        async fn handler(State(hits): State<Hits>, Json(req): Json<ParseRequest>) -> Response {
+            hits.fetch_add(1, Ordering::SeqCst);
+            match req.address.as_str() {
+                "busy" => error(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    ErrorCode::ServiceUnavailable,
+                    Some("0"),
+                ),
+                "full" => error(StatusCode::SERVICE_UNAVAILABLE, ErrorCode::QueueFull, None),
+                "model" => error(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    ErrorCode::ModelNotLoaded,
+                    Some("0"),
+                ),
+                "data" => error(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    ErrorCode::ModelDataError,
+                    Some("0"),
+                ),
+                _ => error(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    ErrorCode::Maintenance,
+                    Some("0"),
+                ),
+            }
+        }
+
+        let hits = Hits::default();
+        let app = Router::new()
+            .route("/api/v1/parse", post(handler))
+            .with_state(hits.clone());
+        let client = serve(app, |b| b.max_retries(2)).await;
+
+        for (address, code, attempts) in [
+            ("busy", ErrorCode::ServiceUnavailable, 3),
+            ("full", ErrorCode::QueueFull, 1),
+            ("model", ErrorCode::ModelNotLoaded, 1),
+            ("data", ErrorCode::ModelDataError, 1),
+            ("maintenance", ErrorCode::Maintenance, 1),
+        ] {
+            hits.store(0, Ordering::SeqCst);
+            let err = client.parse_address(address).await.unwrap_err();
+            assert_eq!(err.code(), Some(code), "{address}");
+            assert_eq!(hits.load(Ordering::SeqCst), attempts, "{address}");
+        }
+    }
+
+    #[tokio::test]
+    async fn parse_all_splits_into_batches() {
+        async fn handler(
+            State(hits): State<Hits>,
+            Json(req): Json<BatchParseRequest>,
+        ) -> Json<ApiResponse<BatchParseResponse>> {
+            hits.fetch_add(1, Ordering::SeqCst);
+            let results: Vec<_> = req
+                .items
+                .into_iter()
+                .enumerate()
+                .map(|(index, item)| BatchParseItemResult {
+                    index,
+                    id: item.id,
+                    original: item.request.address,
+                    components: Some(Default::default()),
+                    hints: AppliedHints::default(),
+                    tokens: None,
+                    error: None,
+                })
+                .collect();
+            let total = results.len();
+            Json(ApiResponse::new(
+                BatchParseResponse {
+                    results,
+                    total,
+                    succeeded: total,
+                    failed: 0,
+                },
+                "req".into(),
+                0,
+            ))
+        }
+
+        let hits = Hits::default();
+        let app = Router::new()
+            .route("/api/v1/parse/batch", post(handler))
+            .with_state(hits.clone());
+        let client = serve(app, |b| b.batch_size(2)).await;
+
+        let items = (0..5).map(|i| BatchParseItem {
+            id: Some(i.to_string()),
+            request: ParseRequest {
+                address: format!("{i} Main St"),
+                ..ParseRequest::default()
+            },
+        });
+        let response = client.parse_all(items).await.unwrap();
+
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+        assert_eq!(response.total, 5);
+        assert_eq!(response.succeeded, 5);
+        for (i, result) in response.results.iter().enumerate() {
+            assert_eq!(result.index, i);
+            assert_eq!(result.id.as_deref(), Some(i.to_string().as_str()));
+        }
+    }
+
+    #[tokio::test]
+    async fn decodes_problem_details() {
+        async fn handler() -> Response {
+            let problem = ProblemDetails {
+                problem_type: ErrorCode::InvalidInput.problem_type(),
+                title: ErrorCode::InvalidInput.title().to_string(),
+                status: 400,
+                detail: "Language code must be 2 characters (ISO 639-1)".to_string(),
+                instance: Some("req".to_string()),
+                code: ErrorCode::InvalidInput,
+                errors: vec![FieldError {
+                    field: "language".to_string(),
+                    code: FieldErrorCode::InvalidFormat,
+                    message: "Language code must be 2 characters (ISO 639-1)".to_string(),
+                }],
+            };
+            (
+                StatusCode::BAD_REQUEST,
+                [(header::CONTENT_TYPE, PROBLEM_JSON)],
+                serde_json::to_string(&problem).unwrap(),
+            )
+                .into_response()
+        }
+
+        let app = Router::new().route("/api/v1/parse", post(handler));
+        let client = serve(app, |b| b).await;
+
+        let err = client.parse_address("1 Main St").await.unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::InvalidInput));
+        let Error::Api { errors, .. } = err else {
+            panic!("expected an API error, got {err}");
+        };
+        assert_eq!(errors[0].field, "language");
+        assert_eq!(errors[0].code, FieldErrorCode::InvalidFormat);
+    }
+
+    #[test]
+    fn rejects_unusable_base_url() {
+        assert!(matches!(
+            Client::new("not a url"),
+            Err(Error::InvalidUrl(_))
+        ));
+    }
+}
@@ -0,0 +1,87 @@
+use std::{fmt, time::Duration};
+
+use libpostal_api_models::{ErrorCode, FieldError};
+use reqwest::StatusCode;
+
+/// Error returned by [`crate::Client`] calls
+#[derive(Debug)]
+pub enum Error {
+    /// The API answered with an error response
+    Api {
+        /// HTTP status of the response
+        status: StatusCode,
+        /// Error code from the response body
+        code: ErrorCode,
+        /// Error message from the response body
+        message: String,
+        /// Every field that failed validation, when the body was problem details
+        errors: Vec<FieldError>,
+        /// Wait the server asked for before retrying, if any
+        retry_after: Option<Duration>,
+    },
+    /// The request could not be sent or its response could not be read
+    Http(reqwest::Error),
+    /// The response was not the JSON the API documents
+    UnexpectedResponse {
+        /// HTTP status of the response
+        status: StatusCode,
+        /// Why the body could not be decoded
+        message: String,
+    },
+    /// The base URL could not be used to build request URLs
+    InvalidUrl(String),
+}
+
+impl Error {
+    /// Error code of an API error response
+    pub fn code(&self) -> Option<ErrorCode> {
+        match self {
+            Error::Api { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+
+    /// HTTP status of the response, when one was received
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::Api { status, .. } | Error::UnexpectedResponse { status, .. } => Some(*status),
+            Error::Http(err) => err.status(),
+            Error::InvalidUrl(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Api {
+                status,
+                code,
+                message,
+                ..
+            } => write!(f, "API error {status} ({}): {message}", code.as_str()),
+            Error::Http(err) => write!(f, "HTTP error: {err}"),
+            Error::UnexpectedResponse { status, message } => {
+                write!(f, "Unexpected response ({status}): {message}")
+            }
+            Error::InvalidUrl(msg) => write!(f, "Invalid URL: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Http(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Http(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,25 @@
+//! Typed async client for the LibPostal API
+//!
+//! ```no_run
+//! use libpostal_api_client::Client;
+//!
+//! # async fn run() -> libpostal_api_client::Result<()> {
+//! let client = Client::builder("http://localhost:3000/api/v1")
+//!     .api_key("my-key")
+//!     .build()?;
+//!
+//! let parsed = client.parse_address("781 Franklin Ave Brooklyn NY 11216").await?;
+//! println!("{:?}", parsed.components.road);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Request and response types are those of the server, re-exported as
+//! [`models`].
+
+mod client;
+mod error;
+
+pub use client::{Client, ClientBuilder};
+pub use error::{Error, Result};
+pub use libpostal_api_models as models;
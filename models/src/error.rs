@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "openapi")]
+use utoipa::ToSchema;
+
+use crate::validation::FieldError;
+
+/// Media type of RFC 7807 problem details error bodies
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Stable machine-readable error code, as sent in error responses
+///
+/// libpostal failures are told apart by status: `UNPROCESSABLE_ADDRESS` (422)
+/// when libpostal cannot process the given address, `MODEL_NOT_LOADED` and
+/// `MODEL_DATA_ERROR` (503) when the model or its data files are unusable, and
+/// `LIBPOSTAL_ERROR` (500) for failures in the bindings themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// The libpostal bindings failed unexpectedly
+    LibpostalError,
+    /// libpostal could not parse or normalize the given address
+    UnprocessableAddress,
+    /// The libpostal model could not be loaded
+    ModelNotLoaded,
+    /// The libpostal data files are missing, unreadable or corrupt
+    ModelDataError,
+    /// The request failed validation
+    InvalidInput,
+    /// The request body is larger than the server accepts
+    PayloadTooLarge,
+    /// The client IP sent too many requests
+    RateLimitExceeded,
+    /// Missing or invalid credentials
+    Unauthorized,
+    /// The API key exceeded its rate or daily quota
+    QuotaExceeded,
+    /// The requested resource does not exist
+    NotFound,
+    /// The resource is not in a state that allows the request
+    Conflict,
+    /// The server failed unexpectedly
+    InternalError,
+    /// The service cannot take requests right now
+    ServiceUnavailable,
+    /// The service is in maintenance mode
+    Maintenance,
+    /// All workers are busy and the wait queue is full
+    QueueFull,
+    /// A code this version does not know, sent by a newer server
+    #[serde(other)]
+    Unknown,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::LibpostalError => "LIBPOSTAL_ERROR",
+            ErrorCode::UnprocessableAddress => "UNPROCESSABLE_ADDRESS",
+            ErrorCode::ModelNotLoaded => "MODEL_NOT_LOADED",
+            ErrorCode::ModelDataError => "MODEL_DATA_ERROR",
+            ErrorCode::InvalidInput => "INVALID_INPUT",
+            ErrorCode::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+            ErrorCode::RateLimitExceeded => "RATE_LIMIT_EXCEEDED",
+            ErrorCode::Unauthorized => "UNAUTHORIZED",
+            ErrorCode::QuotaExceeded => "QUOTA_EXCEEDED",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::Conflict => "CONFLICT",
+            ErrorCode::InternalError => "INTERNAL_ERROR",
+            ErrorCode::ServiceUnavailable => "SERVICE_UNAVAILABLE",
+            ErrorCode::Maintenance => "MAINTENANCE",
+            ErrorCode::QueueFull => "QUEUE_FULL",
+            ErrorCode::Unknown => "UNKNOWN",
+        }
+    }
+
+    /// Short summary of the problem, the same for every occurrence
+    pub fn title(&self) -> &'static str {
+        match self {
+            ErrorCode::LibpostalError => "Address processing failed",
+            ErrorCode::UnprocessableAddress => "Unprocessable address",
+            ErrorCode::ModelNotLoaded => "Model not loaded",
+            ErrorCode::ModelDataError => "Model data unavailable",
+            ErrorCode::InvalidInput => "Invalid input",
+            ErrorCode::PayloadTooLarge => "Payload too large",
+            ErrorCode::RateLimitExceeded => "Rate limit exceeded",
+            ErrorCode::Unauthorized => "Unauthorized",
+            ErrorCode::QuotaExceeded => "Quota exceeded",
+            ErrorCode::NotFound => "Not found",
+            ErrorCode::Conflict => "Conflict",
+            ErrorCode::InternalError => "Internal server error",
+            ErrorCode::ServiceUnavailable => "Service unavailable",
+            ErrorCode::Maintenance => "Down for maintenance",
+            ErrorCode::QueueFull => "Worker queue full",
+            ErrorCode::Unknown => "Unknown error",
+        }
+    }
+
+    /// Problem type URI identifying this code in problem details
+    pub fn problem_type(&self) -> String {
+        format!(
+            "urn:libpostal-api:problem:{}",
+            self.as_str().to_ascii_lowercase().replace('_', "-")
+        )
+    }
+}
+
+/// Body of an error response
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct ErrorResponse {
+    /// Always `false`
+    pub success: bool,
+    /// What went wrong
+    pub error: ErrorDetail,
+}
+
+/// Error details of an error response
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct ErrorDetail {
+    /// Error code
+    pub code: ErrorCode,
+    /// Human-readable error message
+    pub message: String,
+    /// When the error occurred
+    pub timestamp: String,
+}
+
+/// RFC 7807 problem details, sent as `application/problem+json`
+///
+/// Sent instead of [`ErrorResponse`] when the request accepts
+/// `application/problem+json`, or when the server is configured to default to it.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct ProblemDetails {
+    /// URI identifying the kind of problem, one per error code
+    #[serde(rename = "type")]
+    #[cfg_attr(
+        feature = "openapi",
+        schema(example = "urn:libpostal-api:problem:invalid-input")
+    )]
+    pub problem_type: String,
+    /// Short summary of the kind of problem
+    #[cfg_attr(feature = "openapi", schema(example = "Invalid input"))]
+    pub title: String,
+    /// HTTP status code
+    #[cfg_attr(feature = "openapi", schema(example = 400))]
+    pub status: u16,
+    /// Explanation of this occurrence
+    #[cfg_attr(feature = "openapi", schema(example = "Address cannot be empty"))]
+    pub detail: String,
+    /// Request ID of this occurrence, as in the `x-request-id` header
+    pub instance: Option<String>,
+    /// Error code, as in the `code` of an [`ErrorResponse`]
+    pub code: ErrorCode,
+    /// Every field that failed validation, for invalid input
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
@@ -0,0 +1,13 @@
+//! Request and response types of the LibPostal API
+//!
+//! Shared by the server and the client, so both sides agree on the JSON shapes.
+
+pub mod error;
+pub mod requests;
+pub mod responses;
+pub mod validation;
+
+pub use error::*;
+pub use requests::*;
+pub use responses::*;
+pub use validation::{FieldError, FieldErrorCode, ValidationConfig, ValidationErrors};
@@ -0,0 +1,608 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "openapi")]
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    responses::AddressComponents,
+    validation::{FieldErrorCode, ValidationConfig, ValidationErrors},
+};
+
+/// Request to parse an address
+///
+/// Also accepted as query parameters by `GET /api/v1/parse`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema, IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct ParseRequest {
+    /// The address string to parse
+    #[cfg_attr(
+        feature = "openapi",
+        schema(example = "123 Main St, New York, NY 10001")
+    )]
+    pub address: String,
+
+    /// Optional language hint (ISO 639-1 code)
+    #[cfg_attr(feature = "openapi", schema(example = "en"))]
+    pub language: Option<String>,
+
+    /// Optional country hint (ISO 3166-1 alpha-2 code)
+    #[cfg_attr(feature = "openapi", schema(example = "US"))]
+    pub country: Option<String>,
+
+    /// Output format; "tokens" adds ordered spans pointing back into the input
+    pub format: Option<ParseFormat>,
+}
+
+/// Output format of a parse result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ParseFormat {
+    /// Named component fields only
+    Components,
+    /// Named component fields plus labelled spans in input order
+    Tokens,
+}
+
+/// Request to normalize an address
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct NormalizeRequest {
+    /// The address string to normalize
+    #[cfg_attr(feature = "openapi", schema(example = "123 Main St"))]
+    pub address: String,
+
+    /// Normalization level: "light", "medium", or "aggressive"
+    #[cfg_attr(feature = "openapi", schema(example = "medium"))]
+    pub level: Option<String>,
+
+    /// Optional language hints
+    pub languages: Option<Vec<String>>,
+}
+
+/// Request to render an address for mailing
+///
+/// Takes either parsed `components` or a raw `address`, which is parsed first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct FormatRequest {
+    /// Address components to render
+    pub components: Option<AddressComponents>,
+
+    /// Raw address to parse and render instead of `components`
+    #[cfg_attr(feature = "openapi", schema(example = "123 main st new york ny 10001"))]
+    pub address: Option<String>,
+
+    /// Country whose postal template is used (ISO 3166-1 alpha-2 code)
+    #[cfg_attr(feature = "openapi", schema(example = "US"))]
+    pub country: String,
+
+    /// Optional language hint used when parsing `address` (ISO 639-1 code)
+    #[cfg_attr(feature = "openapi", schema(example = "en"))]
+    pub language: Option<String>,
+
+    /// Output layout: "multiline" (default) or "singleline"
+    pub style: Option<FormatStyle>,
+
+    /// Render in uppercase postal style, without periods
+    #[serde(default)]
+    pub uppercase: bool,
+}
+
+/// Request to compare addresses for duplicates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct MatchRequest {
+    /// Two or more addresses to compare pairwise
+    #[cfg_attr(feature = "openapi", schema(example = json!(["123 Main St, New York, NY 10001", "123 Main Street New York 10001"])))]
+    pub addresses: Vec<String>,
+
+    /// Optional language hint (ISO 639-1 code)
+    #[cfg_attr(feature = "openapi", schema(example = "en"))]
+    pub language: Option<String>,
+
+    /// Optional country hint (ISO 3166-1 alpha-2 code)
+    #[cfg_attr(feature = "openapi", schema(example = "US"))]
+    pub country: Option<String>,
+}
+
+/// Layout of a formatted address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum FormatStyle {
+    /// One address line per row, joined with newlines
+    Multiline,
+    /// All address lines joined with commas
+    Singleline,
+}
+
+/// Query parameters for `GET /api/v1/normalize`
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct NormalizeQuery {
+    /// The address string to normalize
+    #[cfg_attr(feature = "openapi", param(example = "123 Main St"))]
+    pub address: String,
+
+    /// Normalization level: "light", "medium", or "aggressive"
+    #[cfg_attr(feature = "openapi", param(example = "medium"))]
+    pub level: Option<String>,
+
+    /// Optional comma-separated language hints
+    #[cfg_attr(feature = "openapi", param(example = "en,fr"))]
+    pub languages: Option<String>,
+}
+
+impl From<NormalizeQuery> for NormalizeRequest {
+    fn from(query: NormalizeQuery) -> Self {
+        Self {
+            address: query.address,
+            level: query.level,
+            languages: query.languages.map(|languages| {
+                languages
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|language| !language.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            }),
+        }
+    }
+}
+
+/// Query parameters for `POST /api/v1/parse/stream`
+///
+/// They apply to every line; JSON lines may override them per line.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct StreamParseQuery {
+    /// Language hint for lines that do not give one (ISO 639-1 code)
+    #[cfg_attr(feature = "openapi", param(example = "en"))]
+    pub language: Option<String>,
+
+    /// Country hint for lines that do not give one (ISO 3166-1 alpha-2 code)
+    #[cfg_attr(feature = "openapi", param(example = "US"))]
+    pub country: Option<String>,
+
+    /// Output format for lines that do not give one
+    pub format: Option<ParseFormat>,
+}
+
+impl StreamParseQuery {
+    /// Build the parse request for one line of the stream
+    ///
+    /// Lines starting with `{` are read as a JSON `ParseRequest`; anything else
+    /// is taken as the address itself.
+    pub fn request_for_line(&self, line: &str) -> Result<ParseRequest, String> {
+        let line = line.trim();
+        let mut request = if line.starts_with('{') {
+            serde_json::from_str::<ParseRequest>(line)
+                .map_err(|e| format!("Invalid JSON line: {e}"))?
+        } else {
+            ParseRequest {
+                address: line.to_string(),
+                language: None,
+                country: None,
+                format: None,
+            }
+        };
+
+        request.language = request.language.or_else(|| self.language.clone());
+        request.country = request.country.or_else(|| self.country.clone());
+        request.format = request.format.or(self.format);
+        Ok(request)
+    }
+}
+
+/// Request to check LibPostal data for a reload
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct ReloadRequest {
+    /// Directory to check; defaults to the one the model in service was loaded from
+    #[cfg_attr(feature = "openapi", schema(example = "/app/data-v2"))]
+    pub data_dir: Option<String>,
+}
+
+/// Request to switch maintenance mode on
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct MaintenanceRequest {
+    /// Message returned with the 503 responses
+    #[cfg_attr(
+        feature = "openapi",
+        schema(example = "Upgrading address data, back in 10 minutes")
+    )]
+    pub message: Option<String>,
+}
+
+/// Options for a bulk file job, given as query parameters on `POST /api/v1/jobs`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema, IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct JobOptions {
+    /// Operation applied to every row
+    pub operation: JobOperation,
+
+    /// Format of the uploaded file; inferred from the Content-Type when omitted
+    pub format: Option<JobInputFormat>,
+
+    /// CSV column or NDJSON field holding the address
+    #[serde(default = "default_address_column")]
+    #[cfg_attr(feature = "openapi", param(example = "address"))]
+    pub column: String,
+
+    /// Optional CSV column or NDJSON field echoed back as each row's id
+    #[cfg_attr(feature = "openapi", param(example = "customer_id"))]
+    pub id_column: Option<String>,
+
+    /// Language hint for parse jobs (ISO 639-1 code)
+    #[cfg_attr(feature = "openapi", param(example = "en"))]
+    pub language: Option<String>,
+
+    /// Country hint for parse jobs (ISO 3166-1 alpha-2 code)
+    #[cfg_attr(feature = "openapi", param(example = "US"))]
+    pub country: Option<String>,
+
+    /// Normalization level for normalize jobs: "light", "medium", or "aggressive"
+    #[cfg_attr(feature = "openapi", param(example = "medium"))]
+    pub level: Option<String>,
+
+    /// Comma-separated language hints for normalize jobs
+    #[cfg_attr(feature = "openapi", param(example = "en,fr"))]
+    pub languages: Option<String>,
+}
+
+fn default_address_column() -> String {
+    "address".to_string()
+}
+
+/// Operation applied to every row of a bulk file job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum JobOperation {
+    /// Parse each address into components
+    Parse,
+    /// Normalize each address into expansions
+    Normalize,
+}
+
+/// Format of a bulk job input file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum JobInputFormat {
+    /// Comma-separated values with a header row
+    Csv,
+    /// One JSON object per line
+    Ndjson,
+}
+
+impl JobOptions {
+    /// Build the parse request for a single row
+    pub fn parse_request(&self, address: String) -> ParseRequest {
+        ParseRequest {
+            address,
+            language: self.language.clone(),
+            country: self.country.clone(),
+            format: None,
+        }
+    }
+
+    /// Build the normalize request for a single row
+    pub fn normalize_request(&self, address: String) -> NormalizeRequest {
+        NormalizeQuery {
+            address,
+            level: self.level.clone(),
+            languages: self.languages.clone(),
+        }
+        .into()
+    }
+
+    pub fn validate(&self, limits: &ValidationConfig) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if self.column.trim().is_empty() {
+            errors.add(
+                "column",
+                FieldErrorCode::Required,
+                "Address column cannot be empty",
+            );
+        }
+
+        // Check the job-wide hints once, using a placeholder address
+        let hints = match self.operation {
+            JobOperation::Parse => self.parse_request("-".to_string()).validate(limits),
+            JobOperation::Normalize => self.normalize_request("-".to_string()).validate(limits),
+        };
+        if let Err(hint_errors) = hints {
+            errors.extend(hint_errors);
+        }
+
+        errors.into_result()
+    }
+}
+
+/// Request to parse many addresses at once
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct BatchParseRequest {
+    /// Addresses to parse, processed in order
+    pub items: Vec<BatchParseItem>,
+}
+
+/// A single address in a batch parse request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct BatchParseItem {
+    /// Optional client-supplied identifier echoed back in the result
+    #[cfg_attr(feature = "openapi", schema(example = "customer-42"))]
+    pub id: Option<String>,
+
+    /// The address and its hints
+    #[serde(flatten)]
+    pub request: ParseRequest,
+}
+
+/// Request to normalize many addresses at once
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct BatchNormalizeRequest {
+    /// Addresses to normalize, processed in order
+    pub items: Vec<BatchNormalizeItem>,
+
+    /// Normalization level applied to items that do not set their own
+    #[cfg_attr(feature = "openapi", schema(example = "medium"))]
+    pub level: Option<String>,
+
+    /// Language hints applied to items that do not set their own
+    pub languages: Option<Vec<String>>,
+}
+
+/// A single address in a batch normalize request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct BatchNormalizeItem {
+    /// Optional client-supplied identifier echoed back in the result
+    #[cfg_attr(feature = "openapi", schema(example = "customer-42"))]
+    pub id: Option<String>,
+
+    /// The address and its per-item options
+    #[serde(flatten)]
+    pub request: NormalizeRequest,
+}
+
+impl BatchNormalizeItem {
+    /// Fill in the batch-wide level and languages where the item has none
+    pub fn with_defaults(mut self, level: Option<&str>, languages: Option<&[String]>) -> Self {
+        if self.request.level.is_none() {
+            self.request.level = level.map(str::to_string);
+        }
+        if self.request.languages.is_none() {
+            self.request.languages = languages.map(<[String]>::to_vec);
+        }
+        self
+    }
+}
+
+impl ParseRequest {
+    /// Whether the caller asked for token spans
+    pub fn wants_tokens(&self) -> bool {
+        self.format == Some(ParseFormat::Tokens)
+    }
+
+    pub fn validate(&self, limits: &ValidationConfig) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        validate_address("address", &self.address, limits, &mut errors);
+        validate_language(self.language.as_deref(), &mut errors);
+        validate_country(self.country.as_deref(), &mut errors);
+        errors.into_result()
+    }
+}
+
+impl FormatRequest {
+    pub fn validate(&self, limits: &ValidationConfig) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+
+        match (&self.components, &self.address) {
+            (Some(_), Some(_)) => errors.add(
+                "address",
+                FieldErrorCode::Conflict,
+                "Provide either components or address, not both",
+            ),
+            (None, None) => errors.add(
+                "address",
+                FieldErrorCode::Required,
+                "Either components or address is required",
+            ),
+            (None, Some(address)) => validate_address("address", address, limits, &mut errors),
+            (Some(_), None) => {}
+        }
+
+        validate_country(Some(self.country.trim()), &mut errors);
+        validate_language(self.language.as_deref(), &mut errors);
+        errors.into_result()
+    }
+}
+
+impl MatchRequest {
+    pub fn validate(&self, limits: &ValidationConfig) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+
+        if self.addresses.len() < 2 {
+            errors.add(
+                "addresses",
+                FieldErrorCode::TooFew,
+                "At least two addresses are required",
+            );
+        }
+
+        if self.addresses.len() > limits.max_match_addresses {
+            errors.add(
+                "addresses",
+                FieldErrorCode::TooMany,
+                format!(
+                    "Too many addresses to match (maximum {})",
+                    limits.max_match_addresses
+                ),
+            );
+        }
+
+        for (i, address) in self.addresses.iter().enumerate() {
+            validate_address(&format!("addresses[{i}]"), address, limits, &mut errors);
+        }
+
+        validate_language(self.language.as_deref(), &mut errors);
+        validate_country(self.country.as_deref(), &mut errors);
+        errors.into_result()
+    }
+}
+
+impl NormalizeRequest {
+    pub fn validate(&self, limits: &ValidationConfig) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        validate_address("address", &self.address, limits, &mut errors);
+
+        // Validate normalization level if provided
+        if let Some(ref level) = self.level {
+            match level.as_str() {
+                "light" | "medium" | "aggressive" => {}
+                _ => errors.add(
+                    "level",
+                    FieldErrorCode::InvalidValue,
+                    "Level must be 'light', 'medium', or 'aggressive'",
+                ),
+            }
+        }
+
+        // Validate language codes if provided
+        if let Some(ref languages) = self.languages {
+            if languages.len() > limits.max_languages {
+                errors.add(
+                    "languages",
+                    FieldErrorCode::TooMany,
+                    format!("Too many language hints (maximum {})", limits.max_languages),
+                );
+            }
+
+            for (i, lang) in languages.iter().enumerate() {
+                if !is_code(lang) {
+                    errors.add(
+                        format!("languages[{i}]"),
+                        FieldErrorCode::InvalidFormat,
+                        "Language codes must be 2 characters (ISO 639-1)",
+                    );
+                }
+            }
+        }
+
+        errors.into_result()
+    }
+}
+
+/// Check that a batch is neither empty nor larger than the configured maximum
+pub fn validate_batch_size(len: usize, limits: &ValidationConfig) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::new();
+
+    if len == 0 {
+        errors.add(
+            "items",
+            FieldErrorCode::Required,
+            "Batch must contain at least one item",
+        );
+    }
+
+    if len > limits.max_batch_size {
+        errors.add(
+            "items",
+            FieldErrorCode::TooMany,
+            format!(
+                "Batch is too large (maximum {} items)",
+                limits.max_batch_size
+            ),
+        );
+    }
+
+    errors.into_result()
+}
+
+fn validate_address(
+    field: &str,
+    address: &str,
+    limits: &ValidationConfig,
+    errors: &mut ValidationErrors,
+) {
+    if address.trim().is_empty() {
+        errors.add(field, FieldErrorCode::Required, "Address cannot be empty");
+    }
+
+    // libpostal takes C strings, which end at the first NUL
+    if address.contains('\0') {
+        errors.add(
+            field,
+            FieldErrorCode::InvalidFormat,
+            "Address cannot contain NUL characters",
+        );
+    }
+
+    if address.chars().count() > limits.max_address_length {
+        errors.add(
+            field,
+            FieldErrorCode::TooLong,
+            format!(
+                "Address is too long (maximum {} characters)",
+                limits.max_address_length
+            ),
+        );
+    }
+}
+
+fn validate_language(language: Option<&str>, errors: &mut ValidationErrors) {
+    if language.is_some_and(|lang| !is_code(lang)) {
+        errors.add(
+            "language",
+            FieldErrorCode::InvalidFormat,
+            "Language code must be 2 characters (ISO 639-1)",
+        );
+    }
+}
+
+fn validate_country(country: Option<&str>, errors: &mut ValidationErrors) {
+    if country.is_some_and(|country| !is_code(country)) {
+        errors.add(
+            "country",
+            FieldErrorCode::InvalidFormat,
+            "Country code must be 2 characters (ISO 3166-1 alpha-2)",
+        );
+    }
+}
+
+/// Whether a language or country code has the 2-character form libpostal expects
+fn is_code(code: &str) -> bool {
+    code.len() == 2 && !code.contains('\0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn address_length_is_counted_in_characters() {
+        let limits = ValidationConfig {
+            max_address_length: 5,
+            ..ValidationConfig::default()
+        };
+        let request = |address: &str| ParseRequest {
+            address: address.to_string(),
+            ..ParseRequest::default()
+        };
+
+        // Five characters, ten bytes
+        assert!(request("ÄÖÜßé").validate(&limits).is_ok());
+
+        let errors = request("ÄÖÜßéa").validate(&limits).unwrap_err();
+        assert_eq!(errors.errors()[0].code, FieldErrorCode::TooLong);
+    }
+}
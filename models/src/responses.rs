@@ -0,0 +1,807 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "openapi")]
+use utoipa::ToSchema;
+
+use crate::{error::ErrorCode, requests::JobOptions};
+
+/// Response for successful API operations
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct ApiResponse<T> {
+    /// Whether the operation was successful
+    pub success: bool,
+    /// The response data
+    pub data: T,
+    /// Request metadata
+    pub meta: ResponseMeta,
+}
+
+/// Response metadata
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct ResponseMeta {
+    /// Request processing time in milliseconds
+    pub processing_time_ms: u64,
+    /// Request ID for tracking
+    pub request_id: String,
+    /// API version
+    pub api_version: String,
+    /// Timestamp of the response
+    pub timestamp: String,
+    /// Result cache usage, present when the cache is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<CacheUsage>,
+}
+
+/// Result cache hits and misses while serving a request
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct CacheUsage {
+    /// Results served from the cache
+    pub hits: usize,
+    /// Results computed by libpostal
+    pub misses: usize,
+}
+
+impl CacheUsage {
+    /// Usage for a request that made a single lookup
+    pub fn single(hit: bool) -> Self {
+        let mut usage = Self::default();
+        usage.record(hit);
+        usage
+    }
+
+    /// Count a lookup as a hit or a miss
+    pub fn record(&mut self, hit: bool) {
+        if hit {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+    }
+}
+
+/// Result of flushing the result cache
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct CacheFlushResponse {
+    /// Number of cached results removed
+    pub flushed: usize,
+}
+
+/// Limits and usage of an API key
+///
+/// Counters are kept in memory and start again from zero when the server restarts.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct ApiKeyUsage {
+    /// Name of the key from the keys file
+    #[cfg_attr(feature = "openapi", schema(example = "billing-pipeline"))]
+    pub name: String,
+    /// Sustained requests per second allowed for the key, if limited
+    pub requests_per_second: Option<u32>,
+    /// Requests allowed per UTC day, if limited
+    pub daily_quota: Option<u64>,
+    /// Requests accepted since midnight UTC
+    pub requests_today: u64,
+    /// Requests left today, if the key has a daily quota
+    pub remaining_today: Option<u64>,
+    /// When the daily count starts again (RFC 3339)
+    pub quota_resets_at: String,
+    /// Requests accepted since the server started
+    pub total_requests: u64,
+    /// Requests refused for exceeding the key's rate or daily quota
+    pub rejected_requests: u64,
+    /// When the key was last used for an accepted request (RFC 3339)
+    pub last_used_at: Option<String>,
+}
+
+/// Usage of every configured API key
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct ApiKeyUsageList {
+    /// One entry per key, in keys file order
+    pub keys: Vec<ApiKeyUsage>,
+}
+
+/// Parsed address components
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct ParsedAddressResponse {
+    /// Original input address
+    #[cfg_attr(
+        feature = "openapi",
+        schema(example = "123 Main St, New York, NY 10001")
+    )]
+    pub original: String,
+    /// Parsed address components
+    pub components: AddressComponents,
+    /// Hints that were passed to the parser
+    pub hints: AppliedHints,
+    /// Labelled spans in input order, present when `format` is "tokens"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<Vec<AddressToken>>,
+}
+
+/// A labelled span of the input address
+///
+/// Offsets count Unicode characters, with `end` exclusive. They are absent when
+/// the value could not be located in the input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct AddressToken {
+    /// Component label, e.g. "house_number" or "road"
+    #[cfg_attr(feature = "openapi", schema(example = "road"))]
+    pub label: String,
+    /// Component value as returned by libpostal
+    #[cfg_attr(feature = "openapi", schema(example = "main st"))]
+    pub value: String,
+    /// Offset of the first character of the span in the input
+    #[cfg_attr(feature = "openapi", schema(example = 4))]
+    pub start: Option<usize>,
+    /// Offset just past the last character of the span in the input
+    #[cfg_attr(feature = "openapi", schema(example = 11))]
+    pub end: Option<usize>,
+}
+
+/// Language and country hints applied when parsing
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct AppliedHints {
+    /// Language hint passed to libpostal, if any
+    #[cfg_attr(feature = "openapi", schema(example = "en"))]
+    pub language: Option<String>,
+    /// Country hint passed to libpostal, if any
+    #[cfg_attr(feature = "openapi", schema(example = "US"))]
+    pub country: Option<String>,
+}
+
+impl AppliedHints {
+    /// Whether no hint was applied
+    pub fn is_empty(&self) -> bool {
+        self.language.is_none() && self.country.is_none()
+    }
+}
+
+/// Individual address components
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(default)]
+pub struct AddressComponents {
+    /// House number (e.g., "123", "123A")
+    #[cfg_attr(feature = "openapi", schema(example = "123"))]
+    pub house_number: Option<String>,
+    /// Road/street name (e.g., "Main St", "Broadway")
+    #[cfg_attr(feature = "openapi", schema(example = "Main St"))]
+    pub road: Option<String>,
+    /// Unit/apartment number (e.g., "Apt 2B", "Unit 5")
+    pub unit: Option<String>,
+    /// Floor/level (e.g., "2nd Floor", "Floor 3")
+    pub level: Option<String>,
+    /// Staircase
+    pub staircase: Option<String>,
+    /// Entrance
+    pub entrance: Option<String>,
+    /// Post office box
+    pub po_box: Option<String>,
+    /// Postcode (e.g., "10001", "SW1A 1AA")
+    #[cfg_attr(feature = "openapi", schema(example = "10001"))]
+    pub postcode: Option<String>,
+    /// Suburb/neighborhood
+    pub suburb: Option<String>,
+    /// City/locality (e.g., "New York", "London")
+    #[cfg_attr(feature = "openapi", schema(example = "New York"))]
+    pub city: Option<String>,
+    /// City district
+    pub city_district: Option<String>,
+    /// Island
+    pub island: Option<String>,
+    /// State/province (e.g., "NY", "California", "Ontario")
+    #[cfg_attr(feature = "openapi", schema(example = "NY"))]
+    pub state: Option<String>,
+    /// State district
+    pub state_district: Option<String>,
+    /// Country region
+    pub country_region: Option<String>,
+    /// Country (e.g., "USA", "United States")
+    #[cfg_attr(feature = "openapi", schema(example = "US"))]
+    pub country: Option<String>,
+    /// World region
+    pub world_region: Option<String>,
+    /// Category (e.g., building type)
+    pub category: Option<String>,
+    /// Near location reference
+    pub near: Option<String>,
+    /// Toponym (place name)
+    pub toponym: Option<String>,
+    /// All other unclassified components
+    pub other: Vec<String>,
+}
+
+/// Result of a batch parse request
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct BatchParseResponse {
+    /// Per-item results in the same order as the request
+    pub results: Vec<BatchParseItemResult>,
+    /// Number of items in the batch
+    pub total: usize,
+    /// Number of items parsed successfully
+    pub succeeded: usize,
+    /// Number of items that failed
+    pub failed: usize,
+}
+
+/// Result for a single address in a batch parse request
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct BatchParseItemResult {
+    /// Position of the item in the request
+    pub index: usize,
+    /// Client-supplied identifier, if any
+    pub id: Option<String>,
+    /// Original input address
+    pub original: String,
+    /// Parsed address components, present when the item succeeded
+    pub components: Option<AddressComponents>,
+    /// Hints that were passed to the parser
+    pub hints: AppliedHints,
+    /// Labelled spans in input order, present when the item asked for "tokens"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<Vec<AddressToken>>,
+    /// Error details, present when the item failed
+    pub error: Option<BatchItemError>,
+}
+
+/// Postal address rendered from components
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct FormattedAddressResponse {
+    /// The address as a single string, joined according to the requested style
+    #[cfg_attr(
+        feature = "openapi",
+        schema(example = "123 Main St\nNew York, NY 10001\nUSA")
+    )]
+    pub formatted: String,
+    /// Address lines in postal order
+    pub lines: Vec<String>,
+    /// Country template that was applied, or "default"
+    #[cfg_attr(feature = "openapi", schema(example = "US"))]
+    pub template: String,
+    /// Components the address was rendered from
+    pub components: AddressComponents,
+}
+
+/// Pairwise comparison of addresses
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct MatchResponse {
+    /// The compared addresses with their parsed components
+    pub addresses: Vec<MatchAddress>,
+    /// One result per pair of addresses
+    pub pairs: Vec<MatchPair>,
+}
+
+/// An address taking part in a match request
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct MatchAddress {
+    /// Position of the address in the request
+    pub index: usize,
+    /// Original input address
+    pub original: String,
+    /// Parsed address components
+    pub components: AddressComponents,
+}
+
+/// Verdict on whether two addresses refer to the same place
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum MatchVerdict {
+    /// Same address once normalized, with no conflicting components
+    Exact,
+    /// Most components agree and none of the identifying ones conflict
+    Likely,
+    /// Some components agree
+    Possible,
+    /// Different addresses
+    None,
+}
+
+/// Comparison of two addresses
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct MatchPair {
+    /// Index of the first address
+    pub a: usize,
+    /// Index of the second address
+    pub b: usize,
+    /// Overall verdict
+    pub verdict: MatchVerdict,
+    /// Weighted share of compared components that agree, from 0 to 1
+    #[cfg_attr(feature = "openapi", schema(example = 0.9))]
+    pub score: f64,
+    /// Components present in both addresses that agree
+    pub agreed: Vec<String>,
+    /// Components present in both addresses that conflict
+    pub conflicted: Vec<String>,
+    /// Whether the whole addresses share a normalized expansion
+    pub shared_expansion: bool,
+}
+
+/// Lifecycle state of a bulk file job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    /// Waiting for a free job runner
+    Queued,
+    /// Rows are being processed
+    Running,
+    /// All rows were processed; the result file is ready
+    Completed,
+    /// The job stopped early; see `error`
+    Failed,
+}
+
+impl JobState {
+    /// Name of the state as it appears in responses
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobState::Queued => "queued",
+            JobState::Running => "running",
+            JobState::Completed => "completed",
+            JobState::Failed => "failed",
+        }
+    }
+}
+
+/// Status and progress of a bulk file job
+///
+/// This is also the job's state file on disk, so it survives restarts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct JobStatus {
+    /// Job identifier
+    #[cfg_attr(
+        feature = "openapi",
+        schema(example = "7f9c2a4e-3b1d-4c8e-9a6f-2d5e8b1c0f3a")
+    )]
+    pub id: String,
+    /// Current state
+    pub state: JobState,
+    /// Options the job was created with
+    pub options: JobOptions,
+    /// When the job was created
+    pub created_at: String,
+    /// When processing last started
+    pub started_at: Option<String>,
+    /// When processing finished or failed
+    pub finished_at: Option<String>,
+    /// Number of rows in the input, known once processing starts
+    pub total_rows: Option<u64>,
+    /// Rows processed so far
+    pub processed_rows: u64,
+    /// Rows that produced an error result
+    pub failed_rows: u64,
+    /// Why the job failed
+    pub error: Option<String>,
+    /// Where to download the NDJSON result once the job has completed
+    pub result_url: Option<String>,
+}
+
+/// Result of a batch normalize request
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct BatchNormalizeResponse {
+    /// Per-item results in the same order as the request
+    pub results: Vec<BatchNormalizeItemResult>,
+    /// Number of items in the batch
+    pub total: usize,
+    /// Number of items normalized successfully
+    pub succeeded: usize,
+    /// Number of items that failed
+    pub failed: usize,
+}
+
+/// Result for a single address in a batch normalize request
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct BatchNormalizeItemResult {
+    /// Position of the item in the request
+    pub index: usize,
+    /// Client-supplied identifier, if any
+    pub id: Option<String>,
+    /// Original input address
+    pub original: String,
+    /// All possible normalized expansions, present when the item succeeded
+    pub expansions: Option<Vec<String>>,
+    /// Number of expansions found, present when the item succeeded
+    pub expansion_count: Option<usize>,
+    /// Error details, present when the item failed
+    pub error: Option<BatchItemError>,
+}
+
+/// One line of a streamed parse response
+///
+/// Each non-blank input line yields exactly one output line, in input order.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(untagged)]
+pub enum StreamParseItem {
+    /// The line was parsed
+    Parsed(Box<ParsedAddressResponse>),
+    /// The line could not be parsed; the stream carries on with the next one
+    Failed(StreamItemError),
+}
+
+/// Error for a single line of a streamed request
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct StreamItemError {
+    /// Line number in the request body, starting at 1
+    pub line: usize,
+    /// The line as received, when it could be read
+    pub original: Option<String>,
+    /// Error details
+    pub error: BatchItemError,
+}
+
+/// Error for a single item in a batch request
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct BatchItemError {
+    /// Error code, the same as for a single-item request failing this way
+    #[cfg_attr(feature = "openapi", schema(example = "INVALID_INPUT"))]
+    pub code: ErrorCode,
+    /// Human-readable error message
+    #[cfg_attr(feature = "openapi", schema(example = "Address cannot be empty"))]
+    pub message: String,
+}
+
+/// Normalized address response
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct NormalizedAddressResponse {
+    /// Original input address
+    #[cfg_attr(feature = "openapi", schema(example = "123 Main St"))]
+    pub original: String,
+    /// All possible normalized expansions
+    pub expansions: Vec<String>,
+    /// Number of expansions found
+    pub expansion_count: usize,
+}
+
+/// Health check response
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct HealthResponse {
+    /// "healthy", or "degraded" when LibPostal is not ready
+    #[cfg_attr(feature = "openapi", schema(example = "healthy"))]
+    pub status: String,
+    /// "ready" or "not_ready", as reported by the readiness probe
+    #[cfg_attr(feature = "openapi", schema(example = "ready"))]
+    pub libpostal_status: String,
+    /// LibPostal worker pool load
+    pub queue: QueueMetrics,
+}
+
+/// LibPostal worker pool metrics
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct QueueMetrics {
+    /// Number of blocking workers
+    #[cfg_attr(feature = "openapi", schema(example = 8))]
+    pub workers: usize,
+    /// Jobs currently running on a worker
+    pub active: usize,
+    /// Jobs waiting for a free worker
+    pub queued: usize,
+    /// Maximum number of jobs allowed to wait
+    #[cfg_attr(feature = "openapi", schema(example = 1024))]
+    pub queue_depth: usize,
+    /// Jobs completed since startup
+    pub completed: u64,
+    /// Jobs rejected because the queue was full
+    pub rejected: u64,
+    /// Average time jobs waited for a worker, in milliseconds
+    pub average_wait_ms: f64,
+}
+
+/// Liveness probe response
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct LivenessResponse {
+    /// Process status
+    #[cfg_attr(feature = "openapi", schema(example = "alive"))]
+    pub status: String,
+    /// Seconds since the process started
+    pub uptime_seconds: u64,
+}
+
+/// Readiness probe response with a detailed health report
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct ReadinessResponse {
+    /// Whether the service can take traffic
+    #[cfg_attr(feature = "openapi", schema(example = "ready"))]
+    pub status: String,
+    /// Directory holding the LibPostal data files
+    #[cfg_attr(feature = "openapi", schema(example = "./data"))]
+    pub data_dir: String,
+    /// Time taken to load the LibPostal model, in milliseconds
+    pub model_load_time_ms: u64,
+    /// Result of the data integrity check run when the model was loaded
+    pub data_integrity: DataIntegrityStatus,
+    /// Process memory usage, when available on this platform
+    pub memory: Option<MemoryInfo>,
+    /// Seconds since the process started
+    pub uptime_seconds: u64,
+    /// Outcome of the most recent self-test, if one has run
+    pub last_self_test: Option<SelfTestResult>,
+    /// LibPostal worker pool load
+    pub queue: QueueMetrics,
+}
+
+/// Result of the data integrity check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct DataIntegrityStatus {
+    /// "verified", "failed" or "skipped"
+    #[cfg_attr(feature = "openapi", schema(example = "verified"))]
+    pub status: String,
+    /// Details when the check failed
+    pub message: Option<String>,
+}
+
+/// Versions recorded in the LibPostal data directory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct DataVersion {
+    /// Base data file version (expansions, numex, transliteration)
+    #[cfg_attr(feature = "openapi", schema(example = "v1.0.0"))]
+    pub base: Option<String>,
+    /// Address parser model version
+    #[cfg_attr(feature = "openapi", schema(example = "v1.0.0"))]
+    pub parser: Option<String>,
+    /// Language classifier model version
+    #[cfg_attr(feature = "openapi", schema(example = "v1.0.0"))]
+    pub language_classifier: Option<String>,
+}
+
+/// The LibPostal model currently serving requests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct ModelStatus {
+    /// Directory the model was loaded from
+    #[cfg_attr(feature = "openapi", schema(example = "./data"))]
+    pub data_dir: String,
+    /// Versions recorded in the data directory
+    pub data_version: DataVersion,
+    /// When the model was loaded (RFC 3339)
+    pub loaded_at: String,
+    /// Time taken to load the model, in milliseconds
+    pub load_time_ms: u64,
+    /// Result of the data integrity check run when the model was loaded
+    pub data_integrity: DataIntegrityStatus,
+}
+
+/// Stage of checking data for a reload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ReloadState {
+    /// The data is being loaded and checked in a separate process
+    Verifying,
+    /// The data passed every check; restart the server on it to put it in service
+    Verified,
+    /// The data cannot be used; see `error`
+    Failed,
+}
+
+/// Progress and outcome of the latest check of data for a reload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct ReloadStatus {
+    pub state: ReloadState,
+    /// Directory being checked
+    #[cfg_attr(feature = "openapi", schema(example = "/app/data-v2"))]
+    pub data_dir: String,
+    /// Directory the model serving requests was loaded from
+    #[cfg_attr(feature = "openapi", schema(example = "./data"))]
+    pub previous_data_dir: String,
+    /// When the check was requested (RFC 3339)
+    pub started_at: String,
+    /// When the check finished (RFC 3339)
+    pub finished_at: Option<String>,
+    /// Canary parses run against the new model, once they have run
+    pub canary: Option<CanaryReport>,
+    /// Why the data cannot be used
+    pub error: Option<String>,
+}
+
+/// Outcome of parsing the canary address set with a model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct CanaryReport {
+    /// Whether every canary parsed as expected
+    pub passed: bool,
+    pub checks: Vec<CanaryCheck>,
+}
+
+/// Outcome of parsing one canary address
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct CanaryCheck {
+    #[cfg_attr(
+        feature = "openapi",
+        schema(example = "781 Franklin Ave Crown Heights Brooklyn NYC NY 11216 USA")
+    )]
+    pub address: String,
+    /// Whether the expected components were found
+    pub passed: bool,
+    /// Expected components that were missing or different
+    #[cfg_attr(feature = "openapi", schema(example = json!(["postcode: expected \"11216\", got none"])))]
+    pub problems: Vec<String>,
+    /// Error returned by libpostal, if the parse failed outright
+    pub error: Option<String>,
+}
+
+/// Maintenance mode state
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct MaintenanceStatus {
+    /// Whether API requests are being turned away
+    pub enabled: bool,
+    /// Message returned with the 503 responses
+    #[cfg_attr(
+        feature = "openapi",
+        schema(example = "Upgrading address data, back in 10 minutes")
+    )]
+    pub message: Option<String>,
+    /// When maintenance mode was switched on (RFC 3339)
+    pub since: Option<String>,
+}
+
+/// Runtime configuration, model and maintenance state
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct AdminStatusResponse {
+    /// Configuration the server was started with, secrets redacted
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub config: serde_json::Value,
+    /// The LibPostal model currently serving requests
+    pub model: ModelStatus,
+    /// Maintenance mode state
+    pub maintenance: MaintenanceStatus,
+}
+
+/// Outcome of a LibPostal self-test parse
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct SelfTestResult {
+    /// Whether the self-test succeeded
+    pub passed: bool,
+    /// When the self-test ran (RFC 3339)
+    pub checked_at: String,
+    /// How long the self-test took, in milliseconds
+    pub duration_ms: u64,
+    /// Error details when the self-test failed
+    pub error: Option<String>,
+}
+
+/// Memory usage information
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct MemoryInfo {
+    /// Used memory in bytes
+    pub used_bytes: u64,
+    /// Available memory in bytes  
+    pub available_bytes: u64,
+}
+
+impl<T> ApiResponse<T> {
+    pub fn new(data: T, request_id: String, processing_time_ms: u64) -> Self {
+        Self {
+            success: true,
+            data,
+            meta: ResponseMeta {
+                processing_time_ms,
+                request_id,
+                api_version: "1.0".to_string(),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                cache: None,
+            },
+        }
+    }
+
+    /// Attach result cache usage to the metadata
+    pub fn with_cache(mut self, cache: Option<CacheUsage>) -> Self {
+        self.meta.cache = cache;
+        self
+    }
+}
+
+impl AddressComponents {
+    /// libpostal labels of the named components, in field order
+    pub const LABELS: [&'static str; 20] = [
+        "house_number",
+        "road",
+        "unit",
+        "level",
+        "staircase",
+        "entrance",
+        "po_box",
+        "postcode",
+        "suburb",
+        "city",
+        "city_district",
+        "island",
+        "state",
+        "state_district",
+        "country_region",
+        "country",
+        "world_region",
+        "category",
+        "near",
+        "toponym",
+    ];
+
+    /// Value of a component by its libpostal label
+    pub fn get(&self, label: &str) -> Option<&str> {
+        let value = match label {
+            "house_number" => &self.house_number,
+            "road" => &self.road,
+            "unit" => &self.unit,
+            "level" => &self.level,
+            "staircase" => &self.staircase,
+            "entrance" => &self.entrance,
+            "po_box" => &self.po_box,
+            "postcode" => &self.postcode,
+            "suburb" => &self.suburb,
+            "city" => &self.city,
+            "city_district" => &self.city_district,
+            "island" => &self.island,
+            "state" => &self.state,
+            "state_district" => &self.state_district,
+            "country_region" => &self.country_region,
+            "country" => &self.country,
+            "world_region" => &self.world_region,
+            "category" => &self.category,
+            "near" => &self.near,
+            "toponym" => &self.toponym,
+            _ => return None,
+        };
+        value.as_deref()
+    }
+}
+
+#[cfg(feature = "libpostal")]
+impl From<libpostal_rs::ParsedAddress> for AddressComponents {
+    fn from(parsed: libpostal_rs::ParsedAddress) -> Self {
+        Self {
+            house_number: parsed.house_number,
+            road: parsed.road,
+            unit: parsed.unit,
+            level: parsed.level,
+            staircase: parsed.staircase,
+            entrance: parsed.entrance,
+            po_box: parsed.po_box,
+            postcode: parsed.postcode,
+            suburb: parsed.suburb,
+            city: parsed.city,
+            city_district: parsed.city_district,
+            island: parsed.island,
+            state: parsed.state,
+            state_district: parsed.state_district,
+            country_region: parsed.country_region,
+            country: parsed.country,
+            world_region: parsed.world_region,
+            category: parsed.category,
+            near: parsed.near,
+            toponym: parsed.toponym,
+            other: parsed.other,
+        }
+    }
+}
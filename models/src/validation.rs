@@ -0,0 +1,137 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "openapi")]
+use utoipa::ToSchema;
+
+/// Request validation limits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ValidationConfig {
+    /// Maximum length of an address in characters
+    pub max_address_length: usize,
+    /// Maximum number of language hints in a single request
+    pub max_languages: usize,
+    /// Maximum number of addresses in a single batch request
+    pub max_batch_size: usize,
+    /// Maximum number of addresses compared in a single match request
+    pub max_match_addresses: usize,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            max_address_length: 1000,
+            max_languages: 10,
+            max_batch_size: 1000,
+            max_match_addresses: 20,
+        }
+    }
+}
+
+/// Why a request field was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum FieldErrorCode {
+    /// The field is missing or empty
+    Required,
+    /// The value is longer than allowed
+    TooLong,
+    /// The value does not have the expected form, such as a 2-letter code
+    InvalidFormat,
+    /// The value is not one of the accepted values
+    InvalidValue,
+    /// The list has fewer entries than required
+    TooFew,
+    /// The list has more entries than allowed
+    TooMany,
+    /// The field cannot be combined with another one that was given
+    Conflict,
+    /// A code this version does not know, sent by a newer server
+    #[serde(other)]
+    Unknown,
+}
+
+/// A request field that failed validation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct FieldError {
+    /// Path of the field in the request, such as `addresses[1]`
+    #[cfg_attr(feature = "openapi", schema(example = "language"))]
+    pub field: String,
+    /// Why the field was rejected
+    pub code: FieldErrorCode,
+    /// Human-readable explanation
+    #[cfg_attr(
+        feature = "openapi",
+        schema(example = "Language code must be 2 characters (ISO 639-1)")
+    )]
+    pub message: String,
+}
+
+/// Every validation failure found in a request
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationErrors(Vec<FieldError>);
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a failure of `field`
+    pub fn add(
+        &mut self,
+        field: impl Into<String>,
+        code: FieldErrorCode,
+        message: impl Into<String>,
+    ) {
+        self.0.push(FieldError {
+            field: field.into(),
+            code,
+            message: message.into(),
+        });
+    }
+
+    /// Record every failure of `other` as well
+    pub fn extend(&mut self, other: ValidationErrors) {
+        self.0.extend(other.0);
+    }
+
+    /// `Ok` when nothing was recorded
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+
+    pub fn errors(&self) -> &[FieldError] {
+        &self.0
+    }
+
+    pub fn into_errors(self) -> Vec<FieldError> {
+        self.0
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, error) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            f.write_str(&error.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+impl From<ValidationErrors> for String {
+    fn from(errors: ValidationErrors) -> Self {
+        errors.to_string()
+    }
+}
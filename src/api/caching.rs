@@ -0,0 +1,145 @@
+use axum::{
+    http::{header, HeaderMap, StatusCode},
+    response::{AppendHeaders, IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{middleware::api_key::X_API_KEY, models::ApiResponse};
+
+/// Request headers that carry an API key
+const KEY_HEADERS: &str = "Authorization, X-API-Key";
+
+/// Build a cacheable JSON response for a GET lookup
+///
+/// The `ETag` is derived from the response data only, since the metadata
+/// (request ID, timestamp, timing) differs on every call. A matching
+/// `If-None-Match` header yields `304 Not Modified` without a body.
+///
+/// Requests made with an API key get a `private` response that varies on the
+/// key headers, so shared caches do not serve it to other clients.
+pub fn cacheable_json<T: Serialize>(
+    headers: &HeaderMap,
+    response: ApiResponse<T>,
+    max_age_secs: u64,
+) -> Response {
+    let keyed = headers.contains_key(header::AUTHORIZATION) || headers.contains_key(X_API_KEY);
+    let mut headers_out = if keyed {
+        vec![
+            (
+                header::CACHE_CONTROL,
+                format!("private, max-age={max_age_secs}"),
+            ),
+            (header::VARY, KEY_HEADERS.to_string()),
+        ]
+    } else {
+        vec![(
+            header::CACHE_CONTROL,
+            format!("public, max-age={max_age_secs}"),
+        )]
+    };
+
+    let Some(etag) = entity_tag(&response.data) else {
+        return (AppendHeaders(headers_out), Json(response)).into_response();
+    };
+    headers_out.push((header::ETAG, etag.clone()));
+    let headers_out = AppendHeaders(headers_out);
+
+    if if_none_match(headers, &etag) {
+        return (StatusCode::NOT_MODIFIED, headers_out).into_response();
+    }
+
+    (headers_out, Json(response)).into_response()
+}
+
+/// Strong entity tag for the serialized data
+fn entity_tag<T: Serialize>(data: &T) -> Option<String> {
+    let body = serde_json::to_vec(data).ok()?;
+    let digest = Sha256::digest(&body);
+
+    let hex: String = digest[..16]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    Some(format!("\"{hex}\""))
+}
+
+/// Whether the request's `If-None-Match` header matches the entity tag
+fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .any(|tag| tag == "*" || tag == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn respond(headers: &HeaderMap) -> Response {
+        let response = ApiResponse::new("781 franklin ave", "request".to_string(), 0);
+        cacheable_json(headers, response, 60)
+    }
+
+    fn header(response: &Response, name: header::HeaderName) -> Option<&str> {
+        response.headers().get(name).and_then(|v| v.to_str().ok())
+    }
+
+    #[test]
+    fn anonymous_responses_are_public() {
+        let response = respond(&HeaderMap::new());
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            header(&response, header::CACHE_CONTROL),
+            Some("public, max-age=60")
+        );
+        assert_eq!(header(&response, header::VARY), None);
+        assert!(header(&response, header::ETAG).is_some());
+    }
+
+    #[test]
+    fn keyed_responses_are_private_and_vary_on_the_key() {
+        for (name, value) in [
+            (header::AUTHORIZATION, "Bearer secret"),
+            (X_API_KEY, "secret"),
+        ] {
+            let mut headers = HeaderMap::new();
+            headers.insert(name, HeaderValue::from_static(value));
+            let response = respond(&headers);
+
+            assert_eq!(
+                header(&response, header::CACHE_CONTROL),
+                Some("private, max-age=60")
+            );
+            assert_eq!(header(&response, header::VARY), Some(KEY_HEADERS));
+        }
+    }
+
+    #[test]
+    fn matching_etags_are_not_modified() {
+        let etag = header(&respond(&HeaderMap::new()), header::ETAG)
+            .unwrap()
+            .to_string();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(X_API_KEY, HeaderValue::from_static("secret"));
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_str(&format!("\"other\", W/{etag}")).unwrap(),
+        );
+        let response = respond(&headers);
+
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(header(&response, header::VARY), Some(KEY_HEADERS));
+
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"other\""));
+        assert_eq!(respond(&headers).status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,175 @@
+use axum::{
+    extract::{
+        rejection::{JsonRejection, QueryRejection},
+        FromRequest, FromRequestParts, OptionalFromRequest, Request,
+    },
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::error::ApiError;
+
+/// JSON request body and response, like [`axum::Json`]
+///
+/// A body that cannot be read or deserialized is rejected with the API's own
+/// `INVALID_INPUT` error, or `PAYLOAD_TOO_LARGE` past the body limit, instead
+/// of axum's plain-text response.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+impl<T, S> FromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::Json(value) = <axum::Json<T> as FromRequest<S>>::from_request(req, state)
+            .await
+            .map_err(json_rejection)?;
+        Ok(Json(value))
+    }
+}
+
+impl<T, S> OptionalFromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        let value = <axum::Json<T> as OptionalFromRequest<S>>::from_request(req, state)
+            .await
+            .map_err(json_rejection)?;
+        Ok(value.map(|axum::Json(value)| Json(value)))
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+/// Query string extractor, like [`axum::extract::Query`]
+///
+/// A query string that cannot be deserialized is rejected with the API's own
+/// `INVALID_INPUT` error instead of axum's plain-text response.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Query<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::extract::Query(value) = axum::extract::Query::from_request_parts(parts, state)
+            .await
+            .map_err(query_rejection)?;
+        Ok(Query(value))
+    }
+}
+
+fn json_rejection(rejection: JsonRejection) -> ApiError {
+    if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        ApiError::PayloadTooLarge("Request body exceeds the size limit".to_string())
+    } else {
+        ApiError::InvalidInput(rejection.body_text())
+    }
+}
+
+fn query_rejection(rejection: QueryRejection) -> ApiError {
+    ApiError::InvalidInput(rejection.body_text())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::header};
+    use http_body_util::Limited;
+    use serde::Deserialize;
+
+    use crate::models::ErrorCode;
+
+    #[derive(Debug, Deserialize)]
+    struct Payload {
+        address: String,
+    }
+
+    fn json_request(body: &str) -> Request {
+        Request::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    async fn extract_json(req: Request) -> Result<Json<Payload>, ApiError> {
+        <Json<Payload> as FromRequest<()>>::from_request(req, &()).await
+    }
+
+    #[tokio::test]
+    async fn json_bodies_are_extracted() {
+        let Json(payload) = extract_json(json_request(r#"{"address": "1 Main St"}"#))
+            .await
+            .unwrap();
+        assert_eq!(payload.address, "1 Main St");
+    }
+
+    #[tokio::test]
+    async fn bad_json_bodies_are_invalid_input() {
+        for body in ["{", "{}", r#"{"address": 5}"#] {
+            let err = extract_json(json_request(body)).await.unwrap_err();
+            assert_eq!(err.error_code(), ErrorCode::InvalidInput, "{body}");
+        }
+
+        let untyped = Request::builder().body(Body::from("{}")).unwrap();
+        let err = extract_json(untyped).await.unwrap_err();
+        assert_eq!(err.error_code(), ErrorCode::InvalidInput);
+    }
+
+    #[tokio::test]
+    async fn oversized_json_bodies_are_payload_too_large() {
+        let body = format!(r#"{{"address": "{}"}}"#, "x".repeat(64));
+        let req = Request::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::new(Limited::new(Body::from(body), 16)))
+            .unwrap();
+
+        let err = extract_json(req).await.unwrap_err();
+        assert_eq!(err.error_code(), ErrorCode::PayloadTooLarge);
+    }
+
+    #[tokio::test]
+    async fn optional_json_bodies_may_be_missing_but_not_malformed() {
+        let empty = Request::builder().body(Body::empty()).unwrap();
+        let missing = <Json<Payload> as OptionalFromRequest<()>>::from_request(empty, &())
+            .await
+            .unwrap();
+        assert!(missing.is_none());
+
+        let err = <Json<Payload> as OptionalFromRequest<()>>::from_request(json_request("{"), &())
+            .await
+            .unwrap_err();
+        assert_eq!(err.error_code(), ErrorCode::InvalidInput);
+    }
+
+    #[tokio::test]
+    async fn bad_query_strings_are_invalid_input() {
+        let (mut parts, ()) = Request::builder()
+            .uri("/parse?language=en")
+            .body(())
+            .unwrap()
+            .into_parts();
+        let err = <Query<Payload> as FromRequestParts<()>>::from_request_parts(&mut parts, &())
+            .await
+            .unwrap_err();
+        assert_eq!(err.error_code(), ErrorCode::InvalidInput);
+        assert!(err.public_message().contains("address"));
+    }
+}
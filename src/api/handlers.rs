@@ -1,9 +1,38 @@
-use axum::{extract::Extension, Json};
-use std::time::Instant;
-use tracing::info;
-use utoipa::OpenApi;
+use axum::{
+    body::{Body, Bytes},
+    extract::{Extension, Path, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use futures_util::StreamExt;
+use std::{collections::HashMap, sync::Arc, time::Instant};
+use tower::ServiceExt;
+use tower_http::services::ServeFile;
+use tracing::{info, warn};
+use utoipa::{
+    openapi::{
+        security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+        Content, Ref, RefOr,
+    },
+    Modify, OpenApi,
+};
 
-use crate::{error::ApiResult, models::*, services::LibPostalService};
+use crate::{
+    api::{
+        caching::cacheable_json,
+        extract::{Json, Query},
+        state::AppState,
+        streaming::LineReader,
+    },
+    config::Config,
+    error::ApiResult,
+    middleware::{ApiKeyStore, AuthenticatedKey},
+    models::*,
+    services::{
+        engine::AddressEngine, formatter, health, jobs::JobManager, maintenance::MaintenanceMode,
+        matcher, metrics::Metrics, reload::ModelReloader, tokens::address_tokens, LibPostalService,
+    },
+};
 
 /// Parse an address into components
 #[utoipa::path(
@@ -13,21 +42,67 @@ use crate::{error::ApiResult, models::*, services::LibPostalService};
     responses(
         (status = 200, description = "Address parsed successfully", body = ApiResponse<ParsedAddressResponse>),
         (status = 400, description = "Invalid input"),
+        (status = 422, description = "libpostal could not process the address (UNPROCESSABLE_ADDRESS)"),
         (status = 429, description = "Rate limit exceeded"),
-        (status = 500, description = "Internal server error")
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Service unavailable, model or data unusable (MODEL_NOT_LOADED, MODEL_DATA_ERROR) or worker queue full")
     ),
     tag = "Address Processing"
 )]
 pub async fn parse_address(
+    State(state): State<AppState>,
     Extension(request_id): Extension<String>,
+    Extension(config): Extension<Arc<Config>>,
     Json(request): Json<ParseRequest>,
 ) -> ApiResult<Json<ApiResponse<ParsedAddressResponse>>> {
+    Ok(Json(parse_one(&state, request_id, &config, request).await?))
+}
+
+/// Parse an address given as query parameters
+///
+/// Equivalent to `POST /api/v1/parse`, but linkable and cacheable. Responses carry
+/// an `ETag` and `Cache-Control`, and a matching `If-None-Match` returns 304.
+#[utoipa::path(
+    get,
+    path = "/api/v1/parse",
+    params(ParseRequest),
+    responses(
+        (status = 200, description = "Address parsed successfully", body = ApiResponse<ParsedAddressResponse>),
+        (status = 304, description = "Not modified since the ETag given in If-None-Match"),
+        (status = 400, description = "Invalid input"),
+        (status = 422, description = "libpostal could not process the address (UNPROCESSABLE_ADDRESS)"),
+        (status = 429, description = "Rate limit exceeded"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Service unavailable, model or data unusable (MODEL_NOT_LOADED, MODEL_DATA_ERROR) or worker queue full")
+    ),
+    tag = "Address Processing"
+)]
+pub async fn parse_address_get(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<String>,
+    Extension(config): Extension<Arc<Config>>,
+    headers: HeaderMap,
+    Query(request): Query<ParseRequest>,
+) -> ApiResult<Response> {
+    let response = parse_one(&state, request_id, &config, request).await?;
+    Ok(cacheable_json(
+        &headers,
+        response,
+        config.server.cache_max_age_secs,
+    ))
+}
+
+/// Validate and parse a single address
+async fn parse_one(
+    state: &AppState,
+    request_id: String,
+    config: &Config,
+    request: ParseRequest,
+) -> ApiResult<ApiResponse<ParsedAddressResponse>> {
     let start_time = Instant::now();
 
     // Validate request
-    request
-        .validate()
-        .map_err(crate::error::ApiError::InvalidInput)?;
+    request.validate(&config.validation)?;
 
     info!(
         request_id = %request_id,
@@ -35,23 +110,37 @@ pub async fn parse_address(
         "Processing parse request"
     );
 
-    // Get LibPostal service
-    let service = LibPostalService::global()?;
+    // Get the address engine
+    let service = state.engine()?;
 
     // Parse the address
-    let parsed = service.parse_address(
-        &request.address,
-        request.language.as_deref(),
-        request.country.as_deref(),
-    ).await?;
+    let parsed = service
+        .parse_address(
+            &request.address,
+            request.language.as_deref(),
+            request.country.as_deref(),
+        )
+        .await?;
+    let cache = service
+        .cache_enabled()
+        .then(|| CacheUsage::single(parsed.hit));
+
+    let tokens = request
+        .wants_tokens()
+        .then(|| address_tokens(&request.address, &parsed.value));
 
     let response_data = ParsedAddressResponse {
+        hints: LibPostalService::resolve_hints(
+            request.language.as_deref(),
+            request.country.as_deref(),
+        ),
         original: request.address,
-        components: AddressComponents::from(parsed),
+        components: AddressComponents::from(parsed.value),
+        tokens,
     };
 
     let processing_time = start_time.elapsed().as_millis() as u64;
-    let response = ApiResponse::new(response_data, request_id, processing_time);
+    let response = ApiResponse::new(response_data, request_id, processing_time).with_cache(cache);
 
     info!(
         request_id = %response.meta.request_id,
@@ -59,6 +148,501 @@ pub async fn parse_address(
         "Parse request completed successfully"
     );
 
+    Ok(response)
+}
+
+/// Parse many addresses in one request
+///
+/// Each item is validated and parsed independently, so a bad address is reported
+/// in its own result instead of failing the whole batch.
+/// Items are parsed concurrently on the worker pool and returned in input order.
+#[utoipa::path(
+    post,
+    path = "/api/v1/parse/batch",
+    request_body = BatchParseRequest,
+    responses(
+        (status = 200, description = "Batch processed; check each result for errors", body = ApiResponse<BatchParseResponse>),
+        (status = 400, description = "Empty or oversized batch"),
+        (status = 429, description = "Rate limit exceeded"),
+        (status = 503, description = "Service unavailable or worker queue full")
+    ),
+    tag = "Address Processing"
+)]
+pub async fn parse_address_batch(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<String>,
+    Extension(config): Extension<Arc<Config>>,
+    Json(request): Json<BatchParseRequest>,
+) -> ApiResult<Json<ApiResponse<BatchParseResponse>>> {
+    let start_time = Instant::now();
+
+    // Validate batch size
+    validate_batch_size(request.items.len(), &config.validation)?;
+
+    info!(
+        request_id = %request_id,
+        batch_size = request.items.len(),
+        "Processing batch parse request"
+    );
+
+    // Get the address engine
+    let service = state.engine()?;
+
+    // Items run concurrently on the workers; buffered keeps them in input order
+    let outcomes: Vec<_> = futures_util::stream::iter(request.items.into_iter().enumerate())
+        .map(|(index, item)| {
+            let (service, config) = (&service, &config);
+            async move {
+                let outcome = match item.request.validate(&config.validation) {
+                    Ok(()) => {
+                        service
+                            .parse_address(
+                                &item.request.address,
+                                item.request.language.as_deref(),
+                                item.request.country.as_deref(),
+                            )
+                            .await
+                    }
+                    Err(errors) => Err(errors.into()),
+                };
+                (index, item, outcome)
+            }
+        })
+        .buffered(service.batch_concurrency())
+        .collect()
+        .await;
+
+    let mut cache = service.cache_enabled().then(CacheUsage::default);
+    let mut results = Vec::with_capacity(outcomes.len());
+    for (index, item, outcome) in outcomes {
+        let (components, tokens, error) = match outcome {
+            Ok(parsed) => {
+                if let Some(cache) = cache.as_mut() {
+                    cache.record(parsed.hit);
+                }
+                let tokens = item
+                    .request
+                    .wants_tokens()
+                    .then(|| address_tokens(&item.request.address, &parsed.value));
+                (Some(AddressComponents::from(parsed.value)), tokens, None)
+            }
+            Err(err) => {
+                warn!(
+                    request_id = %request_id,
+                    index = index,
+                    error = %err,
+                    "Batch parse item failed"
+                );
+                (None, None, Some(BatchItemError::from(&err)))
+            }
+        };
+
+        results.push(BatchParseItemResult {
+            index,
+            id: item.id,
+            hints: LibPostalService::resolve_hints(
+                item.request.language.as_deref(),
+                item.request.country.as_deref(),
+            ),
+            original: item.request.address,
+            components,
+            tokens,
+            error,
+        });
+    }
+
+    let total = results.len();
+    let failed = results
+        .iter()
+        .filter(|result| result.error.is_some())
+        .count();
+    let response_data = BatchParseResponse {
+        results,
+        total,
+        succeeded: total - failed,
+        failed,
+    };
+
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    let response = ApiResponse::new(response_data, request_id, processing_time).with_cache(cache);
+
+    info!(
+        request_id = %response.meta.request_id,
+        processing_time_ms = processing_time,
+        total = total,
+        failed = failed,
+        "Batch parse request completed"
+    );
+
+    Ok(Json(response))
+}
+
+/// Bytes a streamed line may use beyond the longest allowed address, for JSON syntax and hints
+const STREAM_LINE_OVERHEAD_BYTES: usize = 1024;
+
+/// Parse a stream of addresses, one per line
+///
+/// The request body is read line by line as it arrives: a plain address, or a
+/// JSON `ParseRequest` object. The response is an NDJSON stream with one line
+/// per non-blank input line, in order, written as each one is parsed. The next
+/// input line is only read once the previous result has been sent, so a slow
+/// reader slows down the upload too. A bad line yields an error object and the
+/// stream carries on.
+#[utoipa::path(
+    post,
+    path = "/api/v1/parse/stream",
+    params(StreamParseQuery),
+    request_body(
+        description = "One address or JSON ParseRequest per line",
+        content(
+            (String = "text/plain"),
+            (String = "application/x-ndjson")
+        )
+    ),
+    responses(
+        (status = 200, description = "One parse result or error object per line", body = StreamParseItem, content_type = "application/x-ndjson"),
+        (status = 429, description = "Rate limit exceeded"),
+        (status = 503, description = "Service unavailable")
+    ),
+    tag = "Address Processing"
+)]
+pub async fn parse_address_stream(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<String>,
+    Extension(config): Extension<Arc<Config>>,
+    Query(defaults): Query<StreamParseQuery>,
+    body: Body,
+) -> ApiResult<Response> {
+    // Fail with a regular error response before the stream starts
+    let service = state.engine()?;
+
+    info!(
+        request_id = %request_id,
+        "Processing stream parse request"
+    );
+
+    // Address limits count characters, which take up to four bytes each
+    let max_line_bytes = config.validation.max_address_length * 4 + STREAM_LINE_OVERHEAD_BYTES;
+    let state = StreamParseState {
+        service,
+        reader: LineReader::new(body, max_line_bytes),
+        defaults,
+        config,
+        request_id,
+        start_time: Instant::now(),
+        total: 0,
+        failed: 0,
+    };
+
+    let lines = futures_util::stream::unfold(state, move |mut state| async move {
+        let Some((line, text)) = state.reader.next_line().await else {
+            info!(
+                request_id = %state.request_id,
+                processing_time_ms = state.start_time.elapsed().as_millis() as u64,
+                total = state.total,
+                failed = state.failed,
+                "Stream parse request completed"
+            );
+            return None;
+        };
+
+        let item = parse_stream_line(
+            state.service.as_ref(),
+            &state.config,
+            &state.defaults,
+            &state.request_id,
+            line,
+            text,
+        )
+        .await;
+        state.total += 1;
+        if matches!(item, StreamParseItem::Failed(_)) {
+            state.failed += 1;
+        }
+
+        let encoded = serde_json::to_vec(&item).map(|mut encoded| {
+            encoded.push(b'\n');
+            Bytes::from(encoded)
+        });
+        Some((encoded, state))
+    });
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(lines),
+    )
+        .into_response())
+}
+
+/// Progress of a streamed parse request
+struct StreamParseState {
+    service: Arc<dyn AddressEngine>,
+    reader: LineReader,
+    defaults: StreamParseQuery,
+    config: Arc<Config>,
+    request_id: String,
+    start_time: Instant,
+    total: usize,
+    failed: usize,
+}
+
+/// Parse one line of a streamed request into its response line
+async fn parse_stream_line(
+    service: &dyn AddressEngine,
+    config: &Config,
+    defaults: &StreamParseQuery,
+    request_id: &str,
+    line: usize,
+    text: Result<String, String>,
+) -> StreamParseItem {
+    let request = text.clone().and_then(|text| {
+        let request = defaults.request_for_line(&text)?;
+        request.validate(&config.validation)?;
+        Ok(request)
+    });
+
+    let outcome = match request {
+        Ok(request) => service
+            .parse_address(
+                &request.address,
+                request.language.as_deref(),
+                request.country.as_deref(),
+            )
+            .await
+            .map(|parsed| (request, parsed)),
+        Err(msg) => Err(crate::error::ApiError::InvalidInput(msg)),
+    };
+
+    match outcome {
+        Ok((request, parsed)) => {
+            let tokens = request
+                .wants_tokens()
+                .then(|| address_tokens(&request.address, &parsed.value));
+
+            StreamParseItem::Parsed(Box::new(ParsedAddressResponse {
+                hints: LibPostalService::resolve_hints(
+                    request.language.as_deref(),
+                    request.country.as_deref(),
+                ),
+                original: request.address,
+                components: AddressComponents::from(parsed.value),
+                tokens,
+            }))
+        }
+        Err(err) => {
+            warn!(
+                request_id = %request_id,
+                line = line,
+                error = %err,
+                "Stream parse line failed"
+            );
+            StreamParseItem::Failed(StreamItemError {
+                line,
+                original: text.ok(),
+                error: BatchItemError::from(&err),
+            })
+        }
+    }
+}
+
+/// Render an address as a postal address for a country
+///
+/// Accepts parsed components, or a raw address that is parsed first with the
+/// target country as a hint.
+#[utoipa::path(
+    post,
+    path = "/api/v1/format",
+    request_body = FormatRequest,
+    responses(
+        (status = 200, description = "Address formatted successfully", body = ApiResponse<FormattedAddressResponse>),
+        (status = 400, description = "Invalid input"),
+        (status = 422, description = "libpostal could not process the address (UNPROCESSABLE_ADDRESS)"),
+        (status = 429, description = "Rate limit exceeded"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Service unavailable, model or data unusable (MODEL_NOT_LOADED, MODEL_DATA_ERROR) or worker queue full")
+    ),
+    tag = "Address Processing"
+)]
+pub async fn format_address(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<String>,
+    Extension(config): Extension<Arc<Config>>,
+    Json(request): Json<FormatRequest>,
+) -> ApiResult<Json<ApiResponse<FormattedAddressResponse>>> {
+    let start_time = Instant::now();
+
+    // Validate request
+    request.validate(&config.validation)?;
+
+    info!(
+        request_id = %request_id,
+        country = %request.country,
+        style = ?request.style,
+        "Processing format request"
+    );
+
+    let (components, cache) = match request.components {
+        Some(components) => (components, None),
+        None => {
+            let address = request.address.unwrap_or_default();
+            let service = state.engine()?;
+            let parsed = service
+                .parse_address(
+                    &address,
+                    request.language.as_deref(),
+                    Some(&request.country),
+                )
+                .await?;
+            let cache = service
+                .cache_enabled()
+                .then(|| CacheUsage::single(parsed.hit));
+            (AddressComponents::from(parsed.value), cache)
+        }
+    };
+
+    let formatted = formatter::format_address(&components, &request.country, request.uppercase);
+    let separator = match request.style {
+        Some(FormatStyle::Singleline) => ", ",
+        Some(FormatStyle::Multiline) | None => "\n",
+    };
+
+    let response_data = FormattedAddressResponse {
+        formatted: formatted.lines.join(separator),
+        lines: formatted.lines,
+        template: formatted.template,
+        components,
+    };
+
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    let response = ApiResponse::new(response_data, request_id, processing_time).with_cache(cache);
+
+    info!(
+        request_id = %response.meta.request_id,
+        processing_time_ms = processing_time,
+        template = %response.data.template,
+        "Format request completed successfully"
+    );
+
+    Ok(Json(response))
+}
+
+/// Compare addresses to decide whether they refer to the same place
+///
+/// Every address is parsed and normalized, then each pair is scored on the
+/// components they share. Roads and units are compared by their normalized
+/// expansions, so abbreviations still agree.
+#[utoipa::path(
+    post,
+    path = "/api/v1/match",
+    request_body = MatchRequest,
+    responses(
+        (status = 200, description = "Addresses compared successfully", body = ApiResponse<MatchResponse>),
+        (status = 400, description = "Invalid input"),
+        (status = 422, description = "libpostal could not process the address (UNPROCESSABLE_ADDRESS)"),
+        (status = 429, description = "Rate limit exceeded"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Service unavailable, model or data unusable (MODEL_NOT_LOADED, MODEL_DATA_ERROR) or worker queue full")
+    ),
+    tag = "Address Processing"
+)]
+pub async fn match_addresses(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<String>,
+    Extension(config): Extension<Arc<Config>>,
+    Json(request): Json<MatchRequest>,
+) -> ApiResult<Json<ApiResponse<MatchResponse>>> {
+    let start_time = Instant::now();
+
+    // Validate request
+    request.validate(&config.validation)?;
+
+    info!(
+        request_id = %request_id,
+        addresses = request.addresses.len(),
+        "Processing match request"
+    );
+
+    // Get the address engine
+    let service = state.engine()?;
+    let languages = request.language.clone().map(|language| vec![language]);
+
+    let mut cache = service.cache_enabled().then(CacheUsage::default);
+    let mut candidates = Vec::with_capacity(request.addresses.len());
+    for address in &request.addresses {
+        let (parsed, normalized) = tokio::try_join!(
+            service.parse_address(
+                address,
+                request.language.as_deref(),
+                request.country.as_deref(),
+            ),
+            service.normalize_address(address, None, languages.as_deref()),
+        )?;
+
+        let components = AddressComponents::from(parsed.value);
+        let mut hits = vec![parsed.hit, normalized.hit];
+
+        let mut field_expansions = HashMap::new();
+        for field in matcher::EXPANDED_FIELDS {
+            if let Some(value) = components.get(field) {
+                let expanded = service
+                    .normalize_address(value, None, languages.as_deref())
+                    .await?;
+                hits.push(expanded.hit);
+                field_expansions.insert(field, expanded.value.expansions);
+            }
+        }
+
+        if let Some(cache) = cache.as_mut() {
+            hits.into_iter().for_each(|hit| cache.record(hit));
+        }
+
+        candidates.push(matcher::MatchCandidate {
+            components,
+            expansions: normalized.value.expansions,
+            field_expansions,
+        });
+    }
+
+    let mut pairs = Vec::new();
+    for a in 0..candidates.len() {
+        for b in a + 1..candidates.len() {
+            let outcome = matcher::compare(&candidates[a], &candidates[b]);
+            pairs.push(MatchPair {
+                a,
+                b,
+                verdict: outcome.verdict,
+                score: outcome.score,
+                agreed: outcome.agreed,
+                conflicted: outcome.conflicted,
+                shared_expansion: outcome.shared_expansion,
+            });
+        }
+    }
+
+    let addresses = request
+        .addresses
+        .into_iter()
+        .zip(candidates)
+        .enumerate()
+        .map(|(index, (original, candidate))| MatchAddress {
+            index,
+            original,
+            components: candidate.components,
+        })
+        .collect();
+
+    let response_data = MatchResponse { addresses, pairs };
+
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    let response = ApiResponse::new(response_data, request_id, processing_time).with_cache(cache);
+
+    info!(
+        request_id = %response.meta.request_id,
+        processing_time_ms = processing_time,
+        pairs = response.data.pairs.len(),
+        "Match request completed successfully"
+    );
+
     Ok(Json(response))
 }
 
@@ -70,21 +654,70 @@ pub async fn parse_address(
     responses(
         (status = 200, description = "Address normalized successfully", body = ApiResponse<NormalizedAddressResponse>),
         (status = 400, description = "Invalid input"),
+        (status = 422, description = "libpostal could not process the address (UNPROCESSABLE_ADDRESS)"),
         (status = 429, description = "Rate limit exceeded"),
-        (status = 500, description = "Internal server error")
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Service unavailable, model or data unusable (MODEL_NOT_LOADED, MODEL_DATA_ERROR) or worker queue full")
     ),
     tag = "Address Processing"
 )]
 pub async fn normalize_address(
+    State(state): State<AppState>,
     Extension(request_id): Extension<String>,
+    Extension(config): Extension<Arc<Config>>,
     Json(request): Json<NormalizeRequest>,
 ) -> ApiResult<Json<ApiResponse<NormalizedAddressResponse>>> {
+    Ok(Json(
+        normalize_one(&state, request_id, &config, request).await?,
+    ))
+}
+
+/// Normalize an address given as query parameters
+///
+/// Equivalent to `POST /api/v1/normalize`, with `languages` given as a
+/// comma-separated list. Responses carry an `ETag` and `Cache-Control`, and a
+/// matching `If-None-Match` returns 304.
+#[utoipa::path(
+    get,
+    path = "/api/v1/normalize",
+    params(NormalizeQuery),
+    responses(
+        (status = 200, description = "Address normalized successfully", body = ApiResponse<NormalizedAddressResponse>),
+        (status = 304, description = "Not modified since the ETag given in If-None-Match"),
+        (status = 400, description = "Invalid input"),
+        (status = 422, description = "libpostal could not process the address (UNPROCESSABLE_ADDRESS)"),
+        (status = 429, description = "Rate limit exceeded"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Service unavailable, model or data unusable (MODEL_NOT_LOADED, MODEL_DATA_ERROR) or worker queue full")
+    ),
+    tag = "Address Processing"
+)]
+pub async fn normalize_address_get(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<String>,
+    Extension(config): Extension<Arc<Config>>,
+    headers: HeaderMap,
+    Query(query): Query<NormalizeQuery>,
+) -> ApiResult<Response> {
+    let response = normalize_one(&state, request_id, &config, query.into()).await?;
+    Ok(cacheable_json(
+        &headers,
+        response,
+        config.server.cache_max_age_secs,
+    ))
+}
+
+/// Validate and normalize a single address
+async fn normalize_one(
+    state: &AppState,
+    request_id: String,
+    config: &Config,
+    request: NormalizeRequest,
+) -> ApiResult<ApiResponse<NormalizedAddressResponse>> {
     let start_time = Instant::now();
 
     // Validate request
-    request
-        .validate()
-        .map_err(crate::error::ApiError::InvalidInput)?;
+    request.validate(&config.validation)?;
 
     info!(
         request_id = %request_id,
@@ -93,15 +726,21 @@ pub async fn normalize_address(
         "Processing normalize request"
     );
 
-    // Get LibPostal service
-    let service = LibPostalService::global()?;
+    // Get the address engine
+    let service = state.engine()?;
 
     // Normalize the address
-    let normalized = service.normalize_address(
-        &request.address,
-        request.level.as_deref(),
-        request.languages.as_deref(),
-    ).await?;
+    let normalized = service
+        .normalize_address(
+            &request.address,
+            request.level.as_deref(),
+            request.languages.as_deref(),
+        )
+        .await?;
+    let cache = service
+        .cache_enabled()
+        .then(|| CacheUsage::single(normalized.hit));
+    let normalized = normalized.value;
 
     let expansion_count = normalized.expansions.len();
     let response_data = NormalizedAddressResponse {
@@ -111,7 +750,7 @@ pub async fn normalize_address(
     };
 
     let processing_time = start_time.elapsed().as_millis() as u64;
-    let response = ApiResponse::new(response_data, request_id, processing_time);
+    let response = ApiResponse::new(response_data, request_id, processing_time).with_cache(cache);
 
     info!(
         request_id = %response.meta.request_id,
@@ -120,32 +759,675 @@ pub async fn normalize_address(
         "Normalize request completed successfully"
     );
 
+    Ok(response)
+}
+
+/// Normalize many addresses in one request
+///
+/// The batch-wide `level` and `languages` apply to every item that does not set
+/// its own. Each item is validated and normalized independently.
+/// Items are normalized concurrently on the worker pool and returned in input
+/// order.
+#[utoipa::path(
+    post,
+    path = "/api/v1/normalize/batch",
+    request_body = BatchNormalizeRequest,
+    responses(
+        (status = 200, description = "Batch processed; check each result for errors", body = ApiResponse<BatchNormalizeResponse>),
+        (status = 400, description = "Empty or oversized batch"),
+        (status = 429, description = "Rate limit exceeded"),
+        (status = 503, description = "Service unavailable or worker queue full")
+    ),
+    tag = "Address Processing"
+)]
+pub async fn normalize_address_batch(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<String>,
+    Extension(config): Extension<Arc<Config>>,
+    Json(request): Json<BatchNormalizeRequest>,
+) -> ApiResult<Json<ApiResponse<BatchNormalizeResponse>>> {
+    let start_time = Instant::now();
+
+    // Validate batch size
+    validate_batch_size(request.items.len(), &config.validation)?;
+
+    info!(
+        request_id = %request_id,
+        batch_size = request.items.len(),
+        level = ?request.level,
+        "Processing batch normalize request"
+    );
+
+    // Get the address engine
+    let service = state.engine()?;
+
+    // Items run concurrently on the workers; buffered keeps them in input order
+    let (level, languages) = (request.level.as_deref(), request.languages.as_deref());
+    let outcomes: Vec<_> = futures_util::stream::iter(request.items.into_iter().enumerate())
+        .map(|(index, item)| {
+            let (service, config) = (&service, &config);
+            async move {
+                let item = item.with_defaults(level, languages);
+                let outcome = match item.request.validate(&config.validation) {
+                    Ok(()) => {
+                        service
+                            .normalize_address(
+                                &item.request.address,
+                                item.request.level.as_deref(),
+                                item.request.languages.as_deref(),
+                            )
+                            .await
+                    }
+                    Err(errors) => Err(errors.into()),
+                };
+                (index, item, outcome)
+            }
+        })
+        .buffered(service.batch_concurrency())
+        .collect()
+        .await;
+
+    let mut cache = service.cache_enabled().then(CacheUsage::default);
+    let mut results = Vec::with_capacity(outcomes.len());
+    for (index, item, outcome) in outcomes {
+        let (expansions, error) = match outcome {
+            Ok(normalized) => {
+                if let Some(cache) = cache.as_mut() {
+                    cache.record(normalized.hit);
+                }
+                (Some(normalized.value.expansions), None)
+            }
+            Err(err) => {
+                warn!(
+                    request_id = %request_id,
+                    index = index,
+                    error = %err,
+                    "Batch normalize item failed"
+                );
+                (None, Some(BatchItemError::from(&err)))
+            }
+        };
+
+        results.push(BatchNormalizeItemResult {
+            index,
+            id: item.id,
+            original: item.request.address,
+            expansion_count: expansions.as_ref().map(Vec::len),
+            expansions,
+            error,
+        });
+    }
+
+    let total = results.len();
+    let failed = results
+        .iter()
+        .filter(|result| result.error.is_some())
+        .count();
+    let response_data = BatchNormalizeResponse {
+        results,
+        total,
+        succeeded: total - failed,
+        failed,
+    };
+
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    let response = ApiResponse::new(response_data, request_id, processing_time).with_cache(cache);
+
+    info!(
+        request_id = %response.meta.request_id,
+        processing_time_ms = processing_time,
+        total = total,
+        failed = failed,
+        "Batch normalize request completed"
+    );
+
+    Ok(Json(response))
+}
+
+/// Upload a CSV or NDJSON file for bulk processing
+///
+/// The file is sent as the raw request body and the job options as query
+/// parameters. Returns immediately with a job id to poll.
+#[utoipa::path(
+    post,
+    path = "/api/v1/jobs",
+    params(JobOptions),
+    request_body(
+        description = "CSV with a header row, or one JSON object per line",
+        content(
+            (String = "text/csv"),
+            (String = "application/x-ndjson")
+        )
+    ),
+    responses(
+        (status = 202, description = "Job queued", body = ApiResponse<JobStatus>),
+        (status = 400, description = "Invalid options or file"),
+        (status = 413, description = "File too large"),
+        (status = 429, description = "Rate limit exceeded"),
+        (status = 503, description = "Service unavailable")
+    ),
+    tag = "Jobs"
+)]
+pub async fn create_job(
+    Extension(request_id): Extension<String>,
+    Extension(config): Extension<Arc<Config>>,
+    Query(mut options): Query<JobOptions>,
+    headers: HeaderMap,
+    body: Body,
+) -> ApiResult<(
+    StatusCode,
+    [(header::HeaderName, String); 1],
+    Json<ApiResponse<JobStatus>>,
+)> {
+    let start_time = Instant::now();
+
+    // Fall back to the Content-Type when the format is not given explicitly
+    if options.format.is_none() {
+        let content_type = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_ascii_lowercase());
+
+        options.format = match content_type.as_deref() {
+            Some("text/csv") => Some(JobInputFormat::Csv),
+            Some("application/x-ndjson" | "application/ndjson" | "application/jsonl") => {
+                Some(JobInputFormat::Ndjson)
+            }
+            _ => {
+                return Err(crate::error::ApiError::InvalidInput(
+                    "Set format=csv or format=ndjson, or send a text/csv or application/x-ndjson Content-Type".to_string(),
+                ))
+            }
+        };
+    }
+
+    // Validate request
+    options.validate(&config.validation)?;
+
+    let status = JobManager::global()?.create(options, body).await?;
+
+    info!(
+        request_id = %request_id,
+        job_id = %status.id,
+        operation = ?status.options.operation,
+        format = ?status.options.format,
+        "Job queued"
+    );
+
+    let location = format!("/api/v1/jobs/{}", status.id);
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    let response = ApiResponse::new(status, request_id, processing_time);
+
+    Ok((
+        StatusCode::ACCEPTED,
+        [(header::LOCATION, location)],
+        Json(response),
+    ))
+}
+
+/// Status and progress of a bulk job
+#[utoipa::path(
+    get,
+    path = "/api/v1/jobs/{id}",
+    params(("id" = String, Path, description = "Job identifier")),
+    responses(
+        (status = 200, description = "Job status", body = ApiResponse<JobStatus>),
+        (status = 404, description = "Job not found"),
+        (status = 429, description = "Rate limit exceeded")
+    ),
+    tag = "Jobs"
+)]
+pub async fn job_status(
+    Extension(request_id): Extension<String>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<ApiResponse<JobStatus>>> {
+    let start_time = Instant::now();
+
+    let status = JobManager::global()?.status(&id)?;
+
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    Ok(Json(ApiResponse::new(status, request_id, processing_time)))
+}
+
+/// Download the result of a completed bulk job
+///
+/// One JSON object per input row, in input order, shaped like the items of the
+/// matching batch endpoint.
+#[utoipa::path(
+    get,
+    path = "/api/v1/jobs/{id}/result",
+    params(("id" = String, Path, description = "Job identifier")),
+    responses(
+        (status = 200, description = "NDJSON result file", body = String, content_type = "application/x-ndjson"),
+        (status = 404, description = "Job not found"),
+        (status = 409, description = "Job has not completed"),
+        (status = 429, description = "Rate limit exceeded")
+    ),
+    tag = "Jobs"
+)]
+pub async fn job_result(Path(id): Path<String>, request: Request) -> ApiResult<Response> {
+    let path = JobManager::global()?.result_path(&id)?;
+
+    // ServeFile handles range requests so large downloads can resume
+    let mut response = ServeFile::new(path)
+        .oneshot(request)
+        .await
+        .map_err(|e| crate::error::ApiError::Internal(format!("Unable to serve result: {e}")))?
+        .map(Body::new);
+
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/x-ndjson"),
+    );
+    if let Ok(disposition) = format!("attachment; filename=\"{id}.ndjson\"").parse() {
+        headers.insert(header::CONTENT_DISPOSITION, disposition);
+    }
+
+    Ok(response)
+}
+
+/// Limits and usage of the API key making the request
+#[utoipa::path(
+    get,
+    path = "/api/v1/usage",
+    responses(
+        (status = 200, description = "Usage of the calling API key", body = ApiResponse<ApiKeyUsage>),
+        (status = 401, description = "Missing or invalid API key"),
+        (status = 404, description = "API keys are not enabled")
+    ),
+    security(("api_key" = [])),
+    tag = "Usage"
+)]
+pub async fn key_usage(
+    Extension(request_id): Extension<String>,
+    key: Option<Extension<AuthenticatedKey>>,
+) -> ApiResult<Json<ApiResponse<ApiKeyUsage>>> {
+    let start_time = Instant::now();
+
+    let Some(Extension(AuthenticatedKey(key))) = key else {
+        return Err(crate::error::ApiError::NotFound(
+            "API keys are not enabled".to_string(),
+        ));
+    };
+
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    let response = ApiResponse::new(key.usage(), request_id, processing_time);
+
     Ok(Json(response))
 }
 
 /// Health check endpoint
+///
+/// Returns 503 if a test parse fails. Otherwise the status is `degraded` while
+/// `/readyz` would report the service as not ready.
 #[utoipa::path(
     get,
     path = "/api/v1/health",
     responses(
-        (status = 200, description = "Service is healthy", body = ApiResponse<HealthResponse>),
+        (status = 200, description = "Service is answering, healthy or degraded", body = ApiResponse<HealthResponse>),
         (status = 503, description = "Service unavailable")
     ),
     tag = "Health"
 )]
 pub async fn health_check(
+    State(state): State<AppState>,
     Extension(request_id): Extension<String>,
 ) -> ApiResult<Json<ApiResponse<HealthResponse>>> {
     let start_time = Instant::now();
 
-    // Get LibPostal service and check health
-    let service = LibPostalService::global()?;
+    // Get the address engine and check health
+    let service = state.engine()?;
     service.health_check().await?;
 
-    // Get system info (simplified - in production you might want actual memory stats)
+    // Report readiness as /readyz does, from the last self-test and the data check
+    let ready = is_ready(
+        service.last_self_test().as_ref(),
+        &service.model_status().data_integrity,
+    );
     let response_data = HealthResponse {
-        status: "healthy".to_string(),
-        libpostal_status: "ready".to_string(),
+        status: if ready { "healthy" } else { "degraded" }.to_string(),
+        libpostal_status: if ready { "ready" } else { "not_ready" }.to_string(),
+        queue: service.queue_metrics(),
+    };
+
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    let response = ApiResponse::new(response_data, request_id, processing_time);
+
+    Ok(Json(response))
+}
+
+/// Liveness probe
+///
+/// Only reports that the process is running; it never touches LibPostal.
+#[utoipa::path(
+    get,
+    path = "/livez",
+    responses(
+        (status = 200, description = "Process is alive", body = ApiResponse<LivenessResponse>)
+    ),
+    tag = "Health"
+)]
+pub async fn liveness(
+    Extension(request_id): Extension<String>,
+) -> Json<ApiResponse<LivenessResponse>> {
+    let start_time = Instant::now();
+
+    let response_data = LivenessResponse {
+        status: "alive".to_string(),
+        uptime_seconds: health::uptime().as_secs(),
+    };
+
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    Json(ApiResponse::new(response_data, request_id, processing_time))
+}
+
+/// Readiness probe with a detailed health report
+///
+/// Reports the outcome of the last background self-test instead of parsing on
+/// every probe. Returns 503 until a self-test has passed, or if the data failed
+/// its integrity check.
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    responses(
+        (status = 200, description = "Service is ready", body = ApiResponse<ReadinessResponse>),
+        (status = 503, description = "Service is not ready", body = ApiResponse<ReadinessResponse>)
+    ),
+    tag = "Health"
+)]
+pub async fn readiness(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<String>,
+) -> ApiResult<(StatusCode, Json<ApiResponse<ReadinessResponse>>)> {
+    let start_time = Instant::now();
+
+    let service = state.engine()?;
+    let last_self_test = service.last_self_test();
+    let model = service.model_status();
+
+    let ready = is_ready(last_self_test.as_ref(), &model.data_integrity);
+
+    let response_data = ReadinessResponse {
+        status: if ready { "ready" } else { "not_ready" }.to_string(),
+        data_dir: model.data_dir,
+        model_load_time_ms: model.load_time_ms,
+        data_integrity: model.data_integrity,
+        memory: health::memory_info(),
+        uptime_seconds: health::uptime().as_secs(),
+        last_self_test,
+        queue: service.queue_metrics(),
+    };
+
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    let mut response = ApiResponse::new(response_data, request_id, processing_time);
+    response.success = ready;
+
+    Ok((status, Json(response)))
+}
+
+/// Whether a self-test has passed and the data did not fail its integrity check
+fn is_ready(last_self_test: Option<&SelfTestResult>, data_integrity: &DataIntegrityStatus) -> bool {
+    last_self_test.is_some_and(|test| test.passed) && data_integrity.status != "failed"
+}
+
+/// Prometheus metrics endpoint
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses(
+        (status = 200, description = "Metrics in the Prometheus text format", body = String, content_type = "text/plain; version=0.0.4")
+    ),
+    tag = "Health"
+)]
+pub async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    let queue = state.engine().ok().map(|service| service.queue_metrics());
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        Metrics::global().render(queue.as_ref()),
+    )
+}
+
+/// Flush the parse and normalize result cache
+#[utoipa::path(
+    delete,
+    path = "/admin/cache",
+    responses(
+        (status = 200, description = "Cache flushed", body = ApiResponse<CacheFlushResponse>),
+        (status = 401, description = "Missing or invalid admin token"),
+        (status = 503, description = "Service unavailable")
+    ),
+    security(("admin_token" = [])),
+    tag = "Admin"
+)]
+pub async fn flush_cache(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<String>,
+) -> ApiResult<Json<ApiResponse<CacheFlushResponse>>> {
+    let start_time = Instant::now();
+
+    let flushed = state.engine()?.flush_cache();
+
+    info!(
+        request_id = %request_id,
+        flushed = flushed,
+        "Result cache flushed"
+    );
+
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    let response = ApiResponse::new(CacheFlushResponse { flushed }, request_id, processing_time);
+
+    Ok(Json(response))
+}
+
+/// Check LibPostal data for a reload, optionally from another data directory
+///
+/// The check runs in the background and is reported by `GET /admin/reload`.
+/// The model is loaded from the directory and checked with a set of canary
+/// parses in a separate process; the model in service is not touched and keeps
+/// answering requests meanwhile. libpostal keeps one model per process and can
+/// only replace it by tearing it down first, which would stop every call while
+/// the new one loads, so data that passes is put in service by restarting the
+/// server on it, one replica at a time for no downtime.
+#[utoipa::path(
+    post,
+    path = "/admin/reload",
+    request_body(content = Option<ReloadRequest>, description = "Optional data directory to check instead of the current one"),
+    responses(
+        (status = 202, description = "Check started", body = ApiResponse<ReloadStatus>),
+        (status = 400, description = "No usable data in the directory"),
+        (status = 401, description = "Missing or invalid admin token"),
+        (status = 409, description = "A check is already in progress")
+    ),
+    security(("admin_token" = [])),
+    tag = "Admin"
+)]
+pub async fn reload_model(
+    Extension(request_id): Extension<String>,
+    Extension(config): Extension<Arc<Config>>,
+    Extension(reloader): Extension<Arc<ModelReloader>>,
+    request: Option<Json<ReloadRequest>>,
+) -> ApiResult<(StatusCode, Json<ApiResponse<ReloadStatus>>)> {
+    let start_time = Instant::now();
+
+    let Json(request) = request.unwrap_or_default();
+    let data_dir = request
+        .data_dir
+        .map(|dir| dir.trim().to_string())
+        .filter(|dir| !dir.is_empty())
+        .map(std::path::PathBuf::from);
+
+    info!(
+        request_id = %request_id,
+        data_dir = ?data_dir,
+        "Checking LibPostal data for a reload"
+    );
+
+    let status = reloader.start(data_dir, config.data.verify_integrity)?;
+
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    let response = ApiResponse::new(status, request_id, processing_time);
+
+    Ok((StatusCode::ACCEPTED, Json(response)))
+}
+
+/// Progress and outcome of the latest check of data for a reload
+#[utoipa::path(
+    get,
+    path = "/admin/reload",
+    responses(
+        (status = 200, description = "Latest check", body = ApiResponse<ReloadStatus>),
+        (status = 401, description = "Missing or invalid admin token"),
+        (status = 404, description = "No check has been started")
+    ),
+    security(("admin_token" = [])),
+    tag = "Admin"
+)]
+pub async fn reload_status(
+    Extension(request_id): Extension<String>,
+    Extension(reloader): Extension<Arc<ModelReloader>>,
+) -> ApiResult<Json<ApiResponse<ReloadStatus>>> {
+    let start_time = Instant::now();
+
+    let status = reloader.latest().ok_or_else(|| {
+        crate::error::ApiError::NotFound("No reload check has been started".to_string())
+    })?;
+
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    let response = ApiResponse::new(status, request_id, processing_time);
+
+    Ok(Json(response))
+}
+
+/// Switch maintenance mode on
+///
+/// While on, every `/api/v1` request gets a `503` with code `MAINTENANCE` and
+/// the given message. Probes, metrics and admin endpoints keep working.
+#[utoipa::path(
+    put,
+    path = "/admin/maintenance",
+    request_body(content = Option<MaintenanceRequest>, description = "Optional message for clients"),
+    responses(
+        (status = 200, description = "Maintenance mode on", body = ApiResponse<MaintenanceStatus>),
+        (status = 401, description = "Missing or invalid admin token")
+    ),
+    security(("admin_token" = [])),
+    tag = "Admin"
+)]
+pub async fn enable_maintenance(
+    Extension(request_id): Extension<String>,
+    Extension(maintenance): Extension<Arc<MaintenanceMode>>,
+    request: Option<Json<MaintenanceRequest>>,
+) -> Json<ApiResponse<MaintenanceStatus>> {
+    let start_time = Instant::now();
+
+    let Json(request) = request.unwrap_or_default();
+    let status = maintenance.enable(request.message);
+
+    warn!(
+        request_id = %request_id,
+        message = status.message.as_deref().unwrap_or_default(),
+        "Maintenance mode enabled"
+    );
+
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    Json(ApiResponse::new(status, request_id, processing_time))
+}
+
+/// Switch maintenance mode off
+#[utoipa::path(
+    delete,
+    path = "/admin/maintenance",
+    responses(
+        (status = 200, description = "Maintenance mode off", body = ApiResponse<MaintenanceStatus>),
+        (status = 401, description = "Missing or invalid admin token")
+    ),
+    security(("admin_token" = [])),
+    tag = "Admin"
+)]
+pub async fn disable_maintenance(
+    Extension(request_id): Extension<String>,
+    Extension(maintenance): Extension<Arc<MaintenanceMode>>,
+) -> Json<ApiResponse<MaintenanceStatus>> {
+    let start_time = Instant::now();
+
+    let status = maintenance.disable();
+
+    info!(
+        request_id = %request_id,
+        "Maintenance mode disabled"
+    );
+
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    Json(ApiResponse::new(status, request_id, processing_time))
+}
+
+/// Current configuration, data version and maintenance state
+#[utoipa::path(
+    get,
+    path = "/admin/status",
+    responses(
+        (status = 200, description = "Runtime state", body = ApiResponse<AdminStatusResponse>),
+        (status = 401, description = "Missing or invalid admin token"),
+        (status = 503, description = "Service unavailable")
+    ),
+    security(("admin_token" = [])),
+    tag = "Admin"
+)]
+pub async fn admin_status(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<String>,
+    Extension(maintenance): Extension<Arc<MaintenanceMode>>,
+) -> ApiResult<Json<ApiResponse<AdminStatusResponse>>> {
+    let start_time = Instant::now();
+
+    let response_data = AdminStatusResponse {
+        config: serde_json::to_value(state.config().redacted())
+            .map_err(|e| crate::error::ApiError::Internal(e.to_string()))?,
+        model: state.engine()?.model_status(),
+        maintenance: maintenance.status(),
+    };
+
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    let response = ApiResponse::new(response_data, request_id, processing_time);
+
+    Ok(Json(response))
+}
+
+/// Usage of every configured API key
+#[utoipa::path(
+    get,
+    path = "/admin/usage",
+    responses(
+        (status = 200, description = "Usage of every API key", body = ApiResponse<ApiKeyUsageList>),
+        (status = 401, description = "Missing or invalid admin token"),
+        (status = 404, description = "API keys are not enabled")
+    ),
+    security(("admin_token" = [])),
+    tag = "Admin"
+)]
+pub async fn all_key_usage(
+    Extension(request_id): Extension<String>,
+    store: Option<Extension<Arc<ApiKeyStore>>>,
+) -> ApiResult<Json<ApiResponse<ApiKeyUsageList>>> {
+    let start_time = Instant::now();
+
+    let Some(Extension(store)) = store else {
+        return Err(crate::error::ApiError::NotFound(
+            "API keys are not enabled".to_string(),
+        ));
+    };
+
+    let response_data = ApiKeyUsageList {
+        keys: store.usage(),
     };
 
     let processing_time = start_time.elapsed().as_millis() as u64;
@@ -159,28 +1441,118 @@ pub async fn health_check(
 #[openapi(
     paths(
         parse_address,
+        parse_address_get,
+        parse_address_batch,
+        parse_address_stream,
+        format_address,
+        match_addresses,
         normalize_address,
-        health_check
+        normalize_address_get,
+        normalize_address_batch,
+        create_job,
+        job_status,
+        job_result,
+        health_check,
+        liveness,
+        readiness,
+        metrics,
+        key_usage,
+        flush_cache,
+        reload_model,
+        reload_status,
+        enable_maintenance,
+        disable_maintenance,
+        admin_status,
+        all_key_usage
     ),
     components(
         schemas(
             ParseRequest,
+            BatchParseRequest,
+            BatchParseItem,
+            FormatRequest,
+            FormatStyle,
+            MatchRequest,
             NormalizeRequest,
+            BatchNormalizeRequest,
+            BatchNormalizeItem,
+            JobOptions,
+            JobOperation,
+            JobInputFormat,
             ApiResponse<ParsedAddressResponse>,
+            ApiResponse<BatchParseResponse>,
+            ApiResponse<FormattedAddressResponse>,
+            ApiResponse<MatchResponse>,
+            ApiResponse<BatchNormalizeResponse>,
             ApiResponse<NormalizedAddressResponse>,
+            ApiResponse<JobStatus>,
             ApiResponse<HealthResponse>,
+            ApiResponse<LivenessResponse>,
+            ApiResponse<ReadinessResponse>,
+            ApiResponse<CacheFlushResponse>,
+            ApiResponse<ReloadStatus>,
+            ApiResponse<MaintenanceStatus>,
+            ApiResponse<AdminStatusResponse>,
+            ApiResponse<ApiKeyUsage>,
+            ApiResponse<ApiKeyUsageList>,
             ParsedAddressResponse,
+            ParseFormat,
+            AddressToken,
+            AppliedHints,
+            BatchParseResponse,
+            BatchParseItemResult,
+            BatchItemError,
+            StreamParseItem,
+            StreamItemError,
+            FormattedAddressResponse,
+            MatchResponse,
+            MatchAddress,
+            MatchPair,
+            MatchVerdict,
             NormalizedAddressResponse,
+            BatchNormalizeResponse,
+            BatchNormalizeItemResult,
+            JobStatus,
+            JobState,
             HealthResponse,
+            QueueMetrics,
+            LivenessResponse,
+            ReadinessResponse,
+            DataIntegrityStatus,
+            SelfTestResult,
             AddressComponents,
             ResponseMeta,
-            MemoryInfo
+            CacheUsage,
+            CacheFlushResponse,
+            ReloadRequest,
+            ReloadState,
+            ReloadStatus,
+            CanaryReport,
+            CanaryCheck,
+            ModelStatus,
+            DataVersion,
+            MaintenanceRequest,
+            MaintenanceStatus,
+            AdminStatusResponse,
+            ApiKeyUsage,
+            ApiKeyUsageList,
+            MemoryInfo,
+            ErrorResponse,
+            ErrorDetail,
+            ErrorCode,
+            ProblemDetails,
+            FieldError,
+            FieldErrorCode
         )
     ),
     tags(
         (name = "Address Processing", description = "Address parsing and normalization endpoints"),
-        (name = "Health", description = "Service health and monitoring endpoints")
+        (name = "Jobs", description = "Bulk CSV and NDJSON file processing"),
+        (name = "Health", description = "Service health and monitoring endpoints"),
+        (name = "Usage", description = "API key limits and usage, when API keys are enabled"),
+        (name = "Admin", description = "Runtime operations, enabled when an admin token is configured")
     ),
+    modifiers(&SecuritySchemes, &ErrorBodies),
     info(
         title = "LibPostal API",
         version = "1.0.0",
@@ -196,3 +1568,62 @@ pub async fn health_check(
     )
 )]
 pub struct ApiDoc;
+
+/// Registers the admin bearer token and API key security schemes
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "admin_token",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            );
+            components.add_security_scheme(
+                "api_key",
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-API-Key"))),
+            );
+        }
+    }
+}
+
+/// Documents the error body of every 4xx and 5xx response that has no body of its own
+///
+/// Errors are sent as an `ErrorResponse` envelope, or as `ProblemDetails` when
+/// `application/problem+json` is accepted or configured as the default.
+struct ErrorBodies;
+
+impl Modify for ErrorBodies {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for item in openapi.paths.paths.values_mut() {
+            let operations = [
+                &mut item.get,
+                &mut item.put,
+                &mut item.post,
+                &mut item.delete,
+                &mut item.patch,
+            ];
+            for operation in operations.into_iter().flatten() {
+                for (status, response) in operation.responses.responses.iter_mut() {
+                    let RefOr::T(response) = response else {
+                        continue;
+                    };
+                    if !(status.starts_with('4') || status.starts_with('5'))
+                        || !response.content.is_empty()
+                    {
+                        continue;
+                    }
+
+                    response.content.insert(
+                        "application/json".to_string(),
+                        Content::new(Some(Ref::from_schema_name("ErrorResponse"))),
+                    );
+                    response.content.insert(
+                        PROBLEM_JSON.to_string(),
+                        Content::new(Some(Ref::from_schema_name("ProblemDetails"))),
+                    );
+                }
+            }
+        }
+    }
+}
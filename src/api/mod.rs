@@ -1,4 +1,15 @@
+pub mod caching;
+pub mod extract;
 pub mod handlers;
 pub mod routes;
+pub mod state;
+pub mod streaming;
 
-pub use routes::create_api_routes;
+pub use routes::{
+    create_admin_routes, create_api_routes, create_job_routes, create_monitoring_routes,
+    create_stream_routes,
+};
+pub use state::AppState;
+
+#[cfg(test)]
+mod tests;
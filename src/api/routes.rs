@@ -1,14 +1,66 @@
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post, put},
     Router,
 };
 
-use crate::api::handlers;
+use crate::api::{handlers, state::AppState};
 
 /// Create API routes
-pub fn create_api_routes() -> Router {
+pub fn create_api_routes() -> Router<AppState> {
     Router::new()
-        .route("/parse", post(handlers::parse_address))
-        .route("/normalize", post(handlers::normalize_address))
+        .route(
+            "/parse",
+            post(handlers::parse_address).get(handlers::parse_address_get),
+        )
+        .route("/parse/batch", post(handlers::parse_address_batch))
+        .route("/format", post(handlers::format_address))
+        .route("/match", post(handlers::match_addresses))
+        .route(
+            "/normalize",
+            post(handlers::normalize_address).get(handlers::normalize_address_get),
+        )
+        .route("/normalize/batch", post(handlers::normalize_address_batch))
+        .route("/usage", get(handlers::key_usage))
         .route("/health", get(handlers::health_check))
 }
+
+/// Create bulk job routes, which take a much larger request body than the others
+///
+/// Only the standalone server serves these: jobs are run by the global
+/// [`JobManager`](crate::services::jobs::JobManager) on the global LibPostal
+/// model, whatever engine the router state holds.
+pub fn create_job_routes() -> Router<AppState> {
+    Router::new()
+        .route("/jobs", post(handlers::create_job))
+        .route("/jobs/{id}", get(handlers::job_status))
+        .route("/jobs/{id}/result", get(handlers::job_result))
+}
+
+/// Create streaming routes, whose request body has no overall size limit
+pub fn create_stream_routes() -> Router<AppState> {
+    Router::new().route("/parse/stream", post(handlers::parse_address_stream))
+}
+
+/// Create probe and metrics routes, served outside the versioned API
+pub fn create_monitoring_routes() -> Router<AppState> {
+    Router::new()
+        .route("/livez", get(handlers::liveness))
+        .route("/readyz", get(handlers::readiness))
+        .route("/metrics", get(handlers::metrics))
+}
+
+/// Create admin routes; callers must guard them with the admin token middleware
+pub fn create_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/admin/cache", delete(handlers::flush_cache))
+        .route(
+            "/admin/reload",
+            post(handlers::reload_model).get(handlers::reload_status),
+        )
+        .route(
+            "/admin/maintenance",
+            put(handlers::enable_maintenance).delete(handlers::disable_maintenance),
+        )
+        .route("/admin/status", get(handlers::admin_status))
+        .route("/admin/usage", get(handlers::all_key_usage))
+}
@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use crate::{
+    config::Config,
+    error::ApiResult,
+    services::{engine::AddressEngine, LibPostalService},
+};
+
+/// Shared state of the address routes
+#[derive(Clone)]
+pub struct AppState {
+    engine: Arc<dyn Fn() -> ApiResult<Arc<dyn AddressEngine>> + Send + Sync>,
+    config: Arc<Config>,
+}
+
+impl AppState {
+    /// Serve requests from the global LibPostal model
+    ///
+    /// [`LibPostalService::initialize`] must have been called first; until then
+    /// requests get a 503.
+    pub fn libpostal(config: Arc<Config>) -> Self {
+        Self {
+            engine: Arc::new(|| {
+                LibPostalService::global().map(|service| service as Arc<dyn AddressEngine>)
+            }),
+            config,
+        }
+    }
+
+    /// Serve every request from the same engine, such as a fake one in tests
+    pub fn with_engine(engine: Arc<dyn AddressEngine>, config: Arc<Config>) -> Self {
+        Self {
+            engine: Arc::new(move || Ok(engine.clone())),
+            config,
+        }
+    }
+
+    /// The engine to serve a request with
+    pub fn engine(&self) -> ApiResult<Arc<dyn AddressEngine>> {
+        (self.engine)()
+    }
+
+    /// Validation limits, body limits and the other settings the routes use
+    pub fn config(&self) -> &Arc<Config> {
+        &self.config
+    }
+}
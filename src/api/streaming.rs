@@ -0,0 +1,210 @@
+use axum::body::{Body, BodyDataStream};
+use futures_util::StreamExt;
+
+/// Splits a request body into lines as it arrives
+///
+/// Only the current line is buffered, so the body can be arbitrarily long.
+/// Blank lines are skipped and a trailing `\r` is dropped. A line longer than
+/// `max_line_bytes` is discarded up to its newline and reported as an error.
+/// A failed body read is reported once and ends the lines.
+pub struct LineReader {
+    body: BodyDataStream,
+    buffer: Vec<u8>,
+    max_line_bytes: usize,
+    line: usize,
+    oversized: bool,
+    finished: bool,
+}
+
+impl LineReader {
+    pub fn new(body: Body, max_line_bytes: usize) -> Self {
+        Self {
+            body: body.into_data_stream(),
+            buffer: Vec::new(),
+            max_line_bytes,
+            line: 0,
+            oversized: false,
+            finished: false,
+        }
+    }
+
+    /// Next non-blank line with its 1-based line number, or `None` at the end of the body
+    pub async fn next_line(&mut self) -> Option<(usize, Result<String, String>)> {
+        loop {
+            if let Some(newline) = self.buffer.iter().position(|&b| b == b'\n') {
+                let raw: Vec<u8> = self.buffer.drain(..=newline).collect();
+                if let Some(line) = self.take_line(raw) {
+                    return Some(line);
+                }
+                continue;
+            }
+
+            if self.finished {
+                if self.buffer.is_empty() && !self.oversized {
+                    return None;
+                }
+                let raw = std::mem::take(&mut self.buffer);
+                if let Some(line) = self.take_line(raw) {
+                    return Some(line);
+                }
+                continue;
+            }
+
+            if self.buffer.len() > self.max_line_bytes {
+                self.buffer.clear();
+                self.oversized = true;
+            }
+
+            match self.body.next().await {
+                Some(Ok(chunk)) => self.buffer.extend_from_slice(&chunk),
+                Some(Err(e)) => {
+                    // Nothing after a failed read can be trusted. The error is
+                    // reported at the line being read without counting it.
+                    self.buffer.clear();
+                    self.oversized = false;
+                    self.finished = true;
+                    return Some((
+                        self.line + 1,
+                        Err(format!("Failed to read request body: {e}")),
+                    ));
+                }
+                None => self.finished = true,
+            }
+        }
+    }
+
+    /// Turn the raw bytes of one line into the reader's output, or `None` if blank
+    fn take_line(&mut self, mut raw: Vec<u8>) -> Option<(usize, Result<String, String>)> {
+        self.line += 1;
+
+        if raw.last() == Some(&b'\n') {
+            raw.pop();
+        }
+        if raw.last() == Some(&b'\r') {
+            raw.pop();
+        }
+
+        if std::mem::take(&mut self.oversized) || raw.len() > self.max_line_bytes {
+            return Some((
+                self.line,
+                Err(format!("Line exceeds {} bytes", self.max_line_bytes)),
+            ));
+        }
+        if raw.iter().all(u8::is_ascii_whitespace) {
+            return None;
+        }
+
+        Some((
+            self.line,
+            String::from_utf8(raw).map_err(|_| "Line is not valid UTF-8".to_string()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Bytes;
+
+    use super::*;
+
+    fn chunked(chunks: &[&'static [u8]]) -> Body {
+        let chunks: Vec<Result<Bytes, std::io::Error>> = chunks
+            .iter()
+            .map(|chunk| Ok(Bytes::from_static(chunk)))
+            .collect();
+        Body::from_stream(futures_util::stream::iter(chunks))
+    }
+
+    async fn lines(body: Body, max_line_bytes: usize) -> Vec<(usize, Result<String, String>)> {
+        let mut reader = LineReader::new(body, max_line_bytes);
+        let mut lines = Vec::new();
+        while let Some(line) = reader.next_line().await {
+            lines.push(line);
+        }
+        lines
+    }
+
+    fn ok(line: usize, text: &str) -> (usize, Result<String, String>) {
+        (line, Ok(text.to_string()))
+    }
+
+    #[tokio::test]
+    async fn lines_are_numbered_across_chunks_and_blank_lines() {
+        let body = chunked(&[b"1 Main", b" St\n\n  \n2 High St", b"\n3 Low Rd"]);
+
+        assert_eq!(
+            lines(body, 100).await,
+            [ok(1, "1 Main St"), ok(4, "2 High St"), ok(5, "3 Low Rd")]
+        );
+    }
+
+    #[tokio::test]
+    async fn crlf_line_endings_are_stripped() {
+        let body = chunked(&[b"1 Main St\r", b"\n2 High St\r\n\r\n"]);
+
+        assert_eq!(
+            lines(body, 100).await,
+            [ok(1, "1 Main St"), ok(2, "2 High St")]
+        );
+    }
+
+    #[tokio::test]
+    async fn oversized_lines_are_reported_and_skipped() {
+        let too_long = Err("Line exceeds 8 bytes".to_string());
+
+        // Longer than the limit within one chunk, across chunks, and at the end
+        let body = chunked(&[b"123456789\nshort\n1234", b"5678", b"90\nok\n123456789"]);
+
+        assert_eq!(
+            lines(body, 8).await,
+            [
+                (1, too_long.clone()),
+                ok(2, "short"),
+                (3, too_long.clone()),
+                ok(4, "ok"),
+                (5, too_long),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn the_limit_does_not_count_the_line_ending() {
+        let body = chunked(&[b"12345678\r\n"]);
+
+        assert_eq!(lines(body, 8).await, [ok(1, "12345678")]);
+    }
+
+    #[tokio::test]
+    async fn invalid_utf8_fails_only_its_own_line() {
+        let body = chunked(&[b"1 Main St\n\xff\xfe\n2 High St\n"]);
+
+        assert_eq!(
+            lines(body, 100).await,
+            [
+                ok(1, "1 Main St"),
+                (2, Err("Line is not valid UTF-8".to_string())),
+                ok(3, "2 High St"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn read_errors_end_the_lines_without_using_a_number() {
+        let chunks: Vec<Result<Bytes, std::io::Error>> = vec![
+            Ok(Bytes::from_static(b"1 Main St\n2 Hi")),
+            Err(std::io::Error::other("connection reset")),
+            Ok(Bytes::from_static(b"gh St\n")),
+        ];
+        let mut reader =
+            LineReader::new(Body::from_stream(futures_util::stream::iter(chunks)), 100);
+
+        assert_eq!(reader.next_line().await, Some(ok(1, "1 Main St")));
+
+        let (line, error) = reader.next_line().await.unwrap();
+        assert_eq!(line, 2);
+        assert!(error.is_err_and(|e| e.starts_with("Failed to read request body")));
+        assert_eq!(reader.line, 1);
+
+        assert_eq!(reader.next_line().await, None);
+    }
+}
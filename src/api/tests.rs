@@ -0,0 +1,532 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    middleware::from_fn,
+    Router,
+};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+use utoipa::OpenApi;
+
+use crate::{
+    api::{handlers::ApiDoc, AppState},
+    config::{Config, ErrorFormat},
+    error::ApiError,
+    services::fake::FakeEngine,
+};
+
+/// The address routes where the server mounts them, served by `engine`
+fn app(engine: FakeEngine) -> Router {
+    app_with_config(engine, Config::default())
+}
+
+fn app_with_config(engine: FakeEngine, config: Config) -> Router {
+    let state = AppState::with_engine(Arc::new(engine), Arc::new(config));
+    Router::new().nest("/api/v1", crate::router(state))
+}
+
+fn post_json(uri: &str, body: Value) -> Request<Body> {
+    Request::post(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+async fn send(app: Router, request: Request<Body>) -> (StatusCode, Value) {
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body = if body.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(&body).unwrap()
+    };
+    (status, body)
+}
+
+#[tokio::test]
+async fn parse_returns_components_in_envelope() {
+    let response = app(FakeEngine::new())
+        .oneshot(post_json(
+            "/api/v1/parse",
+            json!({ "address": "781 Franklin Ave, Brooklyn, 11216", "country": "us" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let request_id = response.headers()["x-request-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let body: Value = serde_json::from_slice(
+        &axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(body["success"], true);
+    assert_eq!(body["meta"]["request_id"], request_id);
+    assert_eq!(body["data"]["hints"]["country"], "US");
+    assert_eq!(body["data"]["components"]["house_number"], "781");
+    assert_eq!(body["data"]["components"]["road"], "franklin ave");
+    assert_eq!(body["data"]["components"]["city"], "brooklyn");
+    assert_eq!(body["data"]["components"]["postcode"], "11216");
+}
+
+#[tokio::test]
+async fn parse_rejects_invalid_input_before_reaching_the_engine() {
+    let engine = FakeEngine::failing(|| ApiError::Internal("engine was called".to_string()));
+    let (status, body) = send(
+        app(engine),
+        post_json("/api/v1/parse", json!({ "address": "   " })),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["success"], false);
+    assert_eq!(body["error"]["code"], "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn malformed_requests_get_api_errors() {
+    let malformed_body = Request::post("/api/v1/parse")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from("{\"address\": "))
+        .unwrap();
+    let missing_query = Request::get("/api/v1/parse?language=en")
+        .body(Body::empty())
+        .unwrap();
+
+    for request in [malformed_body, missing_query] {
+        let (status, body) = send(app(FakeEngine::new()), request).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["success"], false);
+        assert_eq!(body["error"]["code"], "INVALID_INPUT");
+    }
+}
+
+#[tokio::test]
+async fn parse_get_honours_if_none_match() {
+    let uri = "/api/v1/parse?address=1%20Main%20St%2C%20Springfield";
+    let response = app(FakeEngine::new())
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers()[header::ETAG].clone();
+
+    let response = app(FakeEngine::new())
+        .oneshot(
+            Request::get(uri)
+                .header(header::IF_NONE_MATCH, etag)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+}
+
+#[tokio::test]
+async fn parse_batch_reports_failures_per_item() {
+    let (status, body) = send(
+        app(FakeEngine::new()),
+        post_json(
+            "/api/v1/parse/batch",
+            json!({ "items": [
+                { "id": "a", "address": "1 Main St, Springfield" },
+                { "id": "b", "address": "" }
+            ] }),
+        ),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["total"], 2);
+    assert_eq!(body["data"]["failed"], 1);
+    assert_eq!(body["data"]["results"][0]["id"], "a");
+    assert_eq!(body["data"]["results"][0]["components"]["road"], "main st");
+    assert_eq!(body["data"]["results"][1]["id"], "b");
+    assert_eq!(body["data"]["results"][1]["error"]["code"], "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn parse_batch_keeps_input_order_when_run_concurrently() {
+    let items: Vec<Value> = (0..50)
+        .map(|n| json!({ "id": n.to_string(), "address": format!("{n} Main St, Springfield") }))
+        .collect();
+    let (status, body) = send(
+        app(FakeEngine::with_queue(4, 4)),
+        post_json("/api/v1/parse/batch", json!({ "items": items })),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["succeeded"], 50);
+    for (n, result) in body["data"]["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .enumerate()
+    {
+        assert_eq!(result["index"], n);
+        assert_eq!(result["id"], n.to_string());
+        assert_eq!(result["components"]["house_number"], n.to_string());
+    }
+}
+
+#[tokio::test]
+async fn normalize_batch_keeps_input_order_when_run_concurrently() {
+    let items: Vec<Value> = (0..50)
+        .map(|n| json!({ "id": n.to_string(), "address": format!("{n} Main St") }))
+        .collect();
+    let (status, body) = send(
+        app(FakeEngine::with_queue(4, 4)),
+        post_json("/api/v1/normalize/batch", json!({ "items": items })),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["succeeded"], 50);
+    for (n, result) in body["data"]["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .enumerate()
+    {
+        assert_eq!(result["index"], n);
+        assert_eq!(result["id"], n.to_string());
+        assert_eq!(result["expansions"][1], format!("{n} main street"));
+    }
+}
+
+#[tokio::test]
+async fn parse_stream_answers_every_line_in_order() {
+    let request = Request::post("/api/v1/parse/stream?country=gb")
+        .header(header::CONTENT_TYPE, "text/plain")
+        .body(Body::from("10 Downing St, London\n\n{\"address\": \"\"}\n"))
+        .unwrap();
+    let response = app(FakeEngine::new()).oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let lines: Vec<Value> = body
+        .split(|&byte| byte == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_slice(line).unwrap())
+        .collect();
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["hints"]["country"], "GB");
+    assert_eq!(lines[0]["components"]["city"], "london");
+    assert_eq!(lines[1]["line"], 3);
+    assert_eq!(lines[1]["error"]["code"], "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn normalize_returns_expansions() {
+    let (status, body) = send(
+        app(FakeEngine::new()),
+        post_json("/api/v1/normalize", json!({ "address": "12 Main St" })),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["original"], "12 Main St");
+    assert_eq!(
+        body["data"]["expansions"],
+        json!(["12 main st", "12 main street"])
+    );
+    assert_eq!(body["data"]["expansion_count"], 2);
+}
+
+#[tokio::test]
+async fn engine_errors_keep_their_status_and_code() {
+    let response = app(FakeEngine::failing(|| ApiError::QueueFull))
+        .oneshot(post_json(
+            "/api/v1/normalize",
+            json!({ "address": "12 Main St" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+
+    let (status, body) = send(
+        app(FakeEngine::failing(|| {
+            ApiError::Internal("worker panicked".to_string())
+        })),
+        post_json("/api/v1/parse", json!({ "address": "12 Main St" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body["error"]["code"], "INTERNAL_ERROR");
+    // Internal details stay in the logs
+    assert_eq!(body["error"]["message"], "Internal server error");
+}
+
+#[tokio::test]
+async fn health_check_follows_the_engine() {
+    let health = || Request::get("/api/v1/health").body(Body::empty()).unwrap();
+
+    let (status, body) = send(app(FakeEngine::new()), health()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["status"], "healthy");
+    assert_eq!(body["data"]["libpostal_status"], "ready");
+    assert_eq!(body["data"]["queue"]["workers"], 1);
+
+    let (status, body) = send(app(FakeEngine::corrupt_data()), health()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["status"], "degraded");
+    assert_eq!(body["data"]["libpostal_status"], "not_ready");
+
+    let (status, body) = send(app(FakeEngine::unhealthy()), health()).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["error"]["code"], "SERVICE_UNAVAILABLE");
+}
+
+/// The probe and metrics routes, served by `engine`
+fn monitoring(engine: FakeEngine) -> Router {
+    let state = AppState::with_engine(Arc::new(engine), Arc::new(Config::default()));
+    crate::api::create_monitoring_routes()
+        .with_state(state)
+        .layer(from_fn(crate::middleware::request_id_middleware))
+}
+
+#[tokio::test]
+async fn readiness_follows_the_engine_in_the_state() {
+    let readyz = || Request::get("/readyz").body(Body::empty()).unwrap();
+
+    let (status, body) = send(monitoring(FakeEngine::new()), readyz()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["status"], "ready");
+    assert_eq!(body["data"]["data_dir"], "fake");
+    assert_eq!(body["data"]["last_self_test"]["passed"], true);
+
+    let (status, body) = send(monitoring(FakeEngine::unhealthy()), readyz()).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["data"]["status"], "not_ready");
+}
+
+#[tokio::test]
+async fn metrics_report_the_queue_of_the_engine_in_the_state() {
+    let response = monitoring(FakeEngine::with_queue(3, 7))
+        .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains("libpostal_workers 3"), "{body}");
+}
+
+#[tokio::test]
+async fn jobs_are_not_part_of_the_library_router() {
+    let request = Request::get("/api/v1/jobs/missing")
+        .body(Body::empty())
+        .unwrap();
+    let (status, _) = send(app(FakeEngine::new()), request).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn errors_are_problem_details_when_accepted() {
+    let mut request = post_json(
+        "/api/v1/parse",
+        json!({ "address": " ", "language": "eng", "country": "usa" }),
+    );
+    request.headers_mut().insert(
+        header::ACCEPT,
+        "application/json, application/problem+json;q=0.9"
+            .parse()
+            .unwrap(),
+    );
+
+    let response = app(FakeEngine::new()).oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "application/problem+json"
+    );
+    let request_id = response.headers()["x-request-id"].clone();
+
+    let body: Value = serde_json::from_slice(
+        &axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(body["type"], "urn:libpostal-api:problem:invalid-input");
+    assert_eq!(body["title"], "Invalid input");
+    assert_eq!(body["status"], 400);
+    assert_eq!(body["code"], "INVALID_INPUT");
+    assert_eq!(body["instance"], request_id.to_str().unwrap());
+
+    // Every violation is listed, not only the first
+    let fields: Vec<_> = body["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|error| {
+            (
+                error["field"].as_str().unwrap(),
+                error["code"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            ("address", "required"),
+            ("language", "invalid_format"),
+            ("country", "invalid_format")
+        ]
+    );
+}
+
+#[tokio::test]
+async fn problem_details_can_be_the_default() {
+    let mut config = Config::default();
+    config.server.error_format = ErrorFormat::Problem;
+
+    let response = app_with_config(FakeEngine::failing(|| ApiError::QueueFull), config)
+        .oneshot(post_json(
+            "/api/v1/parse",
+            json!({ "address": "12 Main St" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "application/problem+json"
+    );
+
+    // Without the setting, the envelope stays the default
+    let (_, body) = send(
+        app(FakeEngine::failing(|| ApiError::QueueFull)),
+        post_json("/api/v1/parse", json!({ "address": "12 Main St" })),
+    )
+    .await;
+    assert_eq!(body["error"]["code"], "QUEUE_FULL");
+}
+
+#[test]
+fn openapi_documents_both_error_bodies() {
+    let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+    let content = &doc["paths"]["/api/v1/parse"]["post"]["responses"]["400"]["content"];
+    assert_eq!(
+        content["application/json"]["schema"]["$ref"],
+        "#/components/schemas/ErrorResponse"
+    );
+    assert_eq!(
+        content["application/problem+json"]["schema"]["$ref"],
+        "#/components/schemas/ProblemDetails"
+    );
+
+    // Responses with a body of their own keep it
+    let readiness = &doc["paths"]["/readyz"]["get"]["responses"]["503"]["content"];
+    assert!(readiness["application/problem+json"].is_null());
+    assert!(doc["components"]["schemas"]["FieldError"].is_object());
+}
+
+#[tokio::test]
+async fn libpostal_errors_are_told_apart() {
+    type MakeError = fn() -> libpostal_rs::Error;
+    let cases: [(MakeError, StatusCode, &str); 5] = [
+        (
+            || libpostal_rs::Error::parse_error("libpostal_parse_address returned null"),
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "UNPROCESSABLE_ADDRESS",
+        ),
+        (
+            || libpostal_rs::Error::initialization_failed("setup failed"),
+            StatusCode::SERVICE_UNAVAILABLE,
+            "MODEL_NOT_LOADED",
+        ),
+        (
+            || libpostal_rs::Error::data_error("Data files not found"),
+            StatusCode::SERVICE_UNAVAILABLE,
+            "MODEL_DATA_ERROR",
+        ),
+        (
+            || std::io::Error::other("disk gone").into(),
+            StatusCode::SERVICE_UNAVAILABLE,
+            "MODEL_DATA_ERROR",
+        ),
+        (
+            || libpostal_rs::Error::ffi_error("Invalid address string"),
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "LIBPOSTAL_ERROR",
+        ),
+    ];
+
+    for (error, expected_status, expected_code) in cases {
+        let engine = FakeEngine::failing(move || ApiError::LibPostal(error()));
+        let (status, body) = send(
+            app(engine),
+            post_json("/api/v1/normalize", json!({ "address": "12 Main St" })),
+        )
+        .await;
+        assert_eq!(status, expected_status, "{expected_code}");
+        assert_eq!(body["error"]["code"], expected_code);
+    }
+}
+
+#[tokio::test]
+async fn nul_characters_are_rejected_before_libpostal() {
+    let engine = FakeEngine::failing(|| ApiError::Internal("engine was called".to_string()));
+    let (status, body) = send(
+        app(engine),
+        post_json(
+            "/api/v1/parse",
+            json!({ "address": "12 Main\u{0} St", "country": "u\u{0}" }),
+        ),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body["error"]["message"],
+        "Address cannot contain NUL characters; \
+         Country code must be 2 characters (ISO 3166-1 alpha-2)"
+    );
+}
+
+#[test]
+fn openapi_documents_libpostal_failures() {
+    let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+    for path in ["/api/v1/parse", "/api/v1/normalize"] {
+        let responses = &doc["paths"][path]["post"]["responses"];
+        assert!(responses["422"]["description"]
+            .as_str()
+            .unwrap()
+            .contains("UNPROCESSABLE_ADDRESS"));
+        assert!(responses["503"]["description"]
+            .as_str()
+            .unwrap()
+            .contains("MODEL_NOT_LOADED"));
+    }
+
+    let codes = &doc["components"]["schemas"]["ErrorCode"]["enum"];
+    for code in [
+        "UNPROCESSABLE_ADDRESS",
+        "MODEL_NOT_LOADED",
+        "MODEL_DATA_ERROR",
+    ] {
+        assert!(codes.as_array().unwrap().contains(&json!(code)), "{code}");
+    }
+}
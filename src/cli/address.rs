@@ -0,0 +1,664 @@
+use anyhow::Context;
+use futures_util::{future::BoxFuture, Stream, StreamExt};
+use libpostal_rs::{data::DataManager, NormalizedAddress, ParsedAddress};
+use serde::Serialize;
+use std::{
+    io::{BufWriter, Write},
+    path::Path,
+};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+
+use libpostal_api::{
+    config::Config,
+    error::{ApiError, ApiResult},
+    models::{
+        AddressComponents, BatchItemError, BatchNormalizeItemResult, BatchParseItemResult,
+        NormalizeRequest, ParseRequest, StreamParseQuery,
+    },
+    services::{engine::AddressEngine, tokens::address_tokens, LibPostalService},
+};
+
+use crate::cli::{NormalizeArgs, OutputFormat, ParseArgs};
+
+/// Separator between expansions in a CSV cell
+const CSV_EXPANSION_SEPARATOR: &str = "|";
+
+/// Input lines with their zero-based line numbers
+type Lines = std::pin::Pin<Box<dyn Stream<Item = (usize, std::io::Result<String>)> + Send>>;
+
+/// The libpostal calls made for each line, so tests can stand in for the model
+///
+/// Lines skip the result cache, like bulk jobs.
+trait LineEngine: Sync {
+    fn parse<'a>(
+        &'a self,
+        address: &'a str,
+        language: Option<&'a str>,
+        country: Option<&'a str>,
+    ) -> BoxFuture<'a, ApiResult<ParsedAddress>>;
+
+    fn normalize<'a>(
+        &'a self,
+        address: &'a str,
+        level: Option<&'a str>,
+        languages: Option<&'a [String]>,
+    ) -> BoxFuture<'a, ApiResult<NormalizedAddress>>;
+}
+
+impl LineEngine for LibPostalService {
+    fn parse<'a>(
+        &'a self,
+        address: &'a str,
+        language: Option<&'a str>,
+        country: Option<&'a str>,
+    ) -> BoxFuture<'a, ApiResult<ParsedAddress>> {
+        Box::pin(self.parse_address_uncached(address, language, country))
+    }
+
+    fn normalize<'a>(
+        &'a self,
+        address: &'a str,
+        level: Option<&'a str>,
+        languages: Option<&'a [String]>,
+    ) -> BoxFuture<'a, ApiResult<NormalizedAddress>> {
+        Box::pin(self.normalize_address_uncached(address, level, languages))
+    }
+}
+
+/// Parse every input line and write one result per line
+///
+/// Lines are handled like those of `POST /api/v1/parse/stream`: a line starting
+/// with `{` is a JSON parse request, anything else is the address itself, and
+/// the flags fill in hints the line does not give.
+pub async fn parse(args: ParseArgs, config: &Config) -> anyhow::Result<()> {
+    let defaults = StreamParseQuery {
+        language: args.language,
+        country: args.country,
+        format: None,
+    };
+    // Reject bad flags up front rather than failing every line
+    defaults
+        .request_for_line("-")
+        .and_then(|request| request.validate(&config.validation).map_err(String::from))
+        .map_err(anyhow::Error::msg)?;
+
+    let service = start_service(config).await?;
+    let input = lines(args.input.file.as_deref()).await?;
+    let output = Output::new(args.input.output, parse_csv_header(), std::io::stdout())?;
+
+    parse_lines(
+        &*service,
+        config,
+        &defaults,
+        input,
+        output,
+        service.batch_concurrency(),
+    )
+    .await
+}
+
+/// Parse `input` with up to `concurrency` lines in flight, writing results in input order
+async fn parse_lines<W: Write>(
+    service: &impl LineEngine,
+    config: &Config,
+    defaults: &StreamParseQuery,
+    input: Lines,
+    mut output: Output<W>,
+    concurrency: usize,
+) -> anyhow::Result<()> {
+    let results = input
+        .map(|(index, line)| async move {
+            let line = line?;
+            let request = defaults.request_for_line(&line);
+            Ok::<_, anyhow::Error>(parse_line(service, config, index, &line, request).await)
+        })
+        .buffered(concurrency);
+    futures_util::pin_mut!(results);
+
+    while let Some(result) = results.next().await {
+        let result = result?;
+        output.write(&result, || parse_csv_row(&result))?;
+    }
+    output.finish()
+}
+
+/// Normalize every input line and write one result per line
+pub async fn normalize(args: NormalizeArgs, config: &Config) -> anyhow::Result<()> {
+    let request_for_line = |line: &str| NormalizeRequest {
+        address: line.trim().to_string(),
+        level: args.level.clone(),
+        languages: args.languages.clone(),
+    };
+    // Reject bad flags up front rather than failing every line
+    request_for_line("-")
+        .validate(&config.validation)
+        .map_err(anyhow::Error::msg)?;
+
+    let service = start_service(config).await?;
+    let input = lines(args.input.file.as_deref()).await?;
+    let output = Output::new(args.input.output, normalize_csv_header(), std::io::stdout())?;
+
+    normalize_lines(
+        &*service,
+        config,
+        &request_for_line,
+        input,
+        output,
+        service.batch_concurrency(),
+    )
+    .await
+}
+
+/// Normalize `input` with up to `concurrency` lines in flight, writing results in input order
+async fn normalize_lines<W: Write>(
+    service: &impl LineEngine,
+    config: &Config,
+    request_for_line: &impl Fn(&str) -> NormalizeRequest,
+    input: Lines,
+    mut output: Output<W>,
+    concurrency: usize,
+) -> anyhow::Result<()> {
+    let results = input
+        .map(|(index, line)| async move {
+            let request = request_for_line(&line?);
+            Ok::<_, anyhow::Error>(normalize_line(service, config, index, request).await)
+        })
+        .buffered(concurrency);
+    futures_util::pin_mut!(results);
+
+    while let Some(result) = results.next().await {
+        let result = result?;
+        output.write(&result, || {
+            vec![
+                result.index.to_string(),
+                result.original.clone(),
+                result
+                    .expansion_count
+                    .map(|count| count.to_string())
+                    .unwrap_or_default(),
+                result
+                    .expansions
+                    .as_ref()
+                    .map(|expansions| expansions.join(CSV_EXPANSION_SEPARATOR))
+                    .unwrap_or_default(),
+                error_message(&result.error),
+            ]
+        })?;
+    }
+    output.finish()
+}
+
+async fn parse_line(
+    service: &impl LineEngine,
+    config: &Config,
+    index: usize,
+    line: &str,
+    request: Result<ParseRequest, String>,
+) -> BatchParseItemResult {
+    let request = request.and_then(|request| {
+        request.validate(&config.validation)?;
+        Ok(request)
+    });
+
+    let outcome = match &request {
+        Ok(request) => {
+            service
+                .parse(
+                    &request.address,
+                    request.language.as_deref(),
+                    request.country.as_deref(),
+                )
+                .await
+        }
+        Err(msg) => Err(ApiError::InvalidInput(msg.clone())),
+    };
+
+    let (original, language, country, wants_tokens) = match request {
+        Ok(request) => (
+            request.address.clone(),
+            request.language.clone(),
+            request.country.clone(),
+            request.wants_tokens(),
+        ),
+        Err(_) => (line.trim().to_string(), None, None, false),
+    };
+
+    let (components, tokens, error) = match outcome {
+        Ok(parsed) => {
+            let tokens = wants_tokens.then(|| address_tokens(&original, &parsed));
+            (Some(AddressComponents::from(parsed)), tokens, None)
+        }
+        Err(err) => (None, None, Some(BatchItemError::from(&err))),
+    };
+
+    BatchParseItemResult {
+        index,
+        id: None,
+        hints: LibPostalService::resolve_hints(language.as_deref(), country.as_deref()),
+        original,
+        components,
+        tokens,
+        error,
+    }
+}
+
+async fn normalize_line(
+    service: &impl LineEngine,
+    config: &Config,
+    index: usize,
+    request: NormalizeRequest,
+) -> BatchNormalizeItemResult {
+    let outcome = match request.validate(&config.validation) {
+        Ok(()) => {
+            service
+                .normalize(
+                    &request.address,
+                    request.level.as_deref(),
+                    request.languages.as_deref(),
+                )
+                .await
+        }
+        Err(errors) => Err(errors.into()),
+    };
+
+    let (expansions, error) = match outcome {
+        Ok(normalized) => (Some(normalized.expansions), None),
+        Err(err) => (None, Some(BatchItemError::from(&err))),
+    };
+
+    BatchNormalizeItemResult {
+        index,
+        id: None,
+        original: request.address,
+        expansion_count: expansions.as_ref().map(Vec::len),
+        expansions,
+        error,
+    }
+}
+
+/// Load the model the same way the server does
+///
+/// Missing data is not downloaded: libpostal reports download progress on
+/// standard output, where it would be mixed with the results.
+async fn start_service(config: &Config) -> anyhow::Result<std::sync::Arc<LibPostalService>> {
+    if !DataManager::with_data_dir(&config.data.dir).is_data_available() {
+        anyhow::bail!(
+            "No LibPostal data found in {}; install it with `libpostal-api data install`",
+            config.data.dir.display()
+        );
+    }
+
+    LibPostalService::initialize(&config.data, &config.workers, &config.cache)
+        .await
+        .context("Failed to initialize LibPostal service")?;
+    Ok(LibPostalService::global()?)
+}
+
+/// Non-blank lines of the file, or of standard input for `None` or `-`
+async fn lines(file: Option<&Path>) -> anyhow::Result<Lines> {
+    let reader: Box<dyn AsyncBufRead + Unpin + Send> = match file {
+        Some(path) if path != Path::new("-") => Box::new(BufReader::new(
+            tokio::fs::File::open(path)
+                .await
+                .with_context(|| format!("Unable to open {}", path.display()))?,
+        )),
+        _ => Box::new(BufReader::new(tokio::io::stdin())),
+    };
+    Ok(numbered_lines(reader))
+}
+
+/// Non-blank lines of `reader`
+///
+/// Each line comes with its zero-based line number, which becomes the result's `index`.
+fn numbered_lines(reader: impl AsyncBufRead + Unpin + Send + 'static) -> Lines {
+    let numbered = futures_util::stream::unfold(Some((reader.lines(), 0)), |state| async move {
+        let (mut lines, index) = state?;
+        match lines.next_line().await {
+            Ok(Some(line)) => Some(((index, Ok(line)), Some((lines, index + 1)))),
+            Ok(None) => None,
+            // Nothing after a failed read can be trusted
+            Err(e) => Some(((index, Err(e)), None)),
+        }
+    });
+
+    Box::pin(numbered.filter(|(_, line)| {
+        std::future::ready(line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+    }))
+}
+
+fn parse_csv_header() -> Vec<String> {
+    ["index", "original"]
+        .into_iter()
+        .chain(AddressComponents::LABELS)
+        .chain(["other", "error"])
+        .map(str::to_string)
+        .collect()
+}
+
+fn normalize_csv_header() -> Vec<String> {
+    [
+        "index",
+        "original",
+        "expansion_count",
+        "expansions",
+        "error",
+    ]
+    .map(str::to_string)
+    .to_vec()
+}
+
+fn parse_csv_row(result: &BatchParseItemResult) -> Vec<String> {
+    let components = result.components.as_ref();
+    [result.index.to_string(), result.original.clone()]
+        .into_iter()
+        .chain(AddressComponents::LABELS.iter().map(|label| {
+            components
+                .and_then(|components| components.get(label))
+                .unwrap_or_default()
+                .to_string()
+        }))
+        .chain([
+            components
+                .map(|components| components.other.join(CSV_EXPANSION_SEPARATOR))
+                .unwrap_or_default(),
+            error_message(&result.error),
+        ])
+        .collect()
+}
+
+fn error_message(error: &Option<BatchItemError>) -> String {
+    error
+        .as_ref()
+        .map(|error| error.message.clone())
+        .unwrap_or_default()
+}
+
+/// Results written out, normally to standard output, as they complete
+enum Output<W: Write> {
+    /// A single JSON array
+    Json { out: BufWriter<W>, first: bool },
+    /// One JSON object per line
+    Ndjson(BufWriter<W>),
+    /// A header row, then one row per result
+    Csv(Box<csv::Writer<W>>),
+}
+
+impl<W: Write> Output<W> {
+    fn new(format: OutputFormat, csv_header: Vec<String>, out: W) -> anyhow::Result<Self> {
+        Ok(match format {
+            OutputFormat::Json => {
+                let mut out = BufWriter::new(out);
+                out.write_all(b"[")?;
+                Self::Json { out, first: true }
+            }
+            OutputFormat::Ndjson => Self::Ndjson(BufWriter::new(out)),
+            OutputFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(out);
+                writer.write_record(&csv_header)?;
+                Self::Csv(Box::new(writer))
+            }
+        })
+    }
+
+    fn write<T: Serialize>(
+        &mut self,
+        result: &T,
+        csv_row: impl FnOnce() -> Vec<String>,
+    ) -> anyhow::Result<()> {
+        match self {
+            Self::Json { out, first } => {
+                if !std::mem::take(first) {
+                    out.write_all(b",")?;
+                }
+                out.write_all(b"\n")?;
+                serde_json::to_writer(&mut *out, result)?;
+            }
+            Self::Ndjson(out) => {
+                serde_json::to_writer(&mut *out, result)?;
+                out.write_all(b"\n")?;
+            }
+            Self::Csv(writer) => writer.write_record(csv_row())?,
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> anyhow::Result<()> {
+        match self {
+            Self::Json { mut out, .. } => {
+                out.write_all(b"\n]\n")?;
+                out.flush()?;
+            }
+            Self::Ndjson(mut out) => out.flush()?,
+            Self::Csv(mut writer) => writer.flush()?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads the road as the whole address, lowercased, and normalizes to the same
+    struct Lowercase;
+
+    impl LineEngine for Lowercase {
+        fn parse<'a>(
+            &'a self,
+            address: &'a str,
+            _language: Option<&'a str>,
+            _country: Option<&'a str>,
+        ) -> BoxFuture<'a, ApiResult<ParsedAddress>> {
+            Box::pin(async move {
+                Ok(ParsedAddress {
+                    road: Some(address.to_lowercase()),
+                    ..ParsedAddress::default()
+                })
+            })
+        }
+
+        fn normalize<'a>(
+            &'a self,
+            address: &'a str,
+            _level: Option<&'a str>,
+            _languages: Option<&'a [String]>,
+        ) -> BoxFuture<'a, ApiResult<NormalizedAddress>> {
+            Box::pin(async move {
+                Ok(NormalizedAddress {
+                    original: address.to_string(),
+                    expansions: vec![address.to_lowercase()],
+                })
+            })
+        }
+    }
+
+    fn input(text: &'static str) -> Lines {
+        numbered_lines(text.as_bytes())
+    }
+
+    async fn parse_output(text: &'static str, format: OutputFormat) -> String {
+        let defaults = StreamParseQuery {
+            language: None,
+            country: Some("us".to_string()),
+            format: None,
+        };
+        let mut out = Vec::new();
+        let output = Output::new(format, parse_csv_header(), &mut out).unwrap();
+        parse_lines(
+            &Lowercase,
+            &Config::default(),
+            &defaults,
+            input(text),
+            output,
+            4,
+        )
+        .await
+        .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    async fn normalize_output(text: &'static str, format: OutputFormat) -> String {
+        let config = Config::default();
+        let request_for_line = |line: &str| NormalizeRequest {
+            address: line.trim().to_string(),
+            level: None,
+            languages: None,
+        };
+        let mut out = Vec::new();
+        let output = Output::new(format, normalize_csv_header(), &mut out).unwrap();
+        normalize_lines(
+            &Lowercase,
+            &config,
+            &request_for_line,
+            input(text),
+            output,
+            4,
+        )
+        .await
+        .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn ndjson(output: &str) -> Vec<serde_json::Value> {
+        output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn blank_lines_are_skipped_but_keep_their_line_numbers() {
+        let lines: Vec<_> = input("1 Main St\n\n   \n2 High St\n")
+            .map(|(index, line)| (index, line.unwrap()))
+            .collect()
+            .await;
+        assert_eq!(
+            lines,
+            [(0, "1 Main St".to_string()), (3, "2 High St".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn json_output_is_one_array_in_input_order() {
+        let output = parse_output("1 Main St\n\n2 High St\n3 Mill Rd\n", OutputFormat::Json).await;
+        let results: Vec<serde_json::Value> = serde_json::from_str(&output).unwrap();
+
+        let rows: Vec<_> = results
+            .iter()
+            .map(|result| {
+                (
+                    result["index"].as_u64().unwrap(),
+                    result["components"]["road"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(rows, [(0, "1 main st"), (2, "2 high st"), (3, "3 mill rd")]);
+        assert_eq!(results[0]["hints"]["country"], "US");
+
+        let empty = parse_output("\n\n", OutputFormat::Json).await;
+        assert_eq!(
+            serde_json::from_str::<Vec<serde_json::Value>>(&empty).unwrap(),
+            Vec::<serde_json::Value>::new()
+        );
+    }
+
+    #[tokio::test]
+    async fn ndjson_output_has_one_line_per_result() {
+        let output = parse_output(
+            "1 Main St\n{\"address\": \"2 High St\", \"country\": \"gb\"}\n",
+            OutputFormat::Ndjson,
+        )
+        .await;
+        let results = ndjson(&output);
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["original"], "1 Main St");
+        assert_eq!(results[1]["original"], "2 High St");
+        // A hint on the line beats the flag
+        assert_eq!(results[1]["hints"]["country"], "GB");
+    }
+
+    #[tokio::test]
+    async fn invalid_rows_get_an_error_and_the_rest_carry_on() {
+        let output = parse_output(
+            "1 Main St\n{\"address\": \nbad\0address\n{\"address\": \"2 High St\", \"language\": \"english\"}\n3 Mill Rd\n",
+            OutputFormat::Ndjson,
+        )
+        .await;
+        let results = ndjson(&output);
+
+        assert_eq!(results.len(), 5);
+        for (result, index) in results.iter().zip(0..) {
+            assert_eq!(result["index"], index);
+        }
+        for failed in &results[1..4] {
+            assert_eq!(failed["error"]["code"], "INVALID_INPUT", "{failed}");
+            assert!(failed["components"].is_null());
+        }
+        assert!(results[1]["error"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Invalid JSON line"));
+        assert_eq!(results[2]["original"], "bad\0address");
+        assert_eq!(results[4]["components"]["road"], "3 mill rd");
+    }
+
+    #[tokio::test]
+    async fn csv_output_has_a_header_and_a_row_per_result() {
+        let output = parse_output("1 Main St\n\n{oops\n", OutputFormat::Csv).await;
+        let mut reader = csv::Reader::from_reader(output.as_bytes());
+
+        let header = reader.headers().unwrap().clone();
+        assert_eq!(
+            header.iter().collect::<Vec<_>>(),
+            parse_csv_header()
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+        );
+        let column = |name: &str| header.iter().position(|h| h == name).unwrap();
+
+        let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(&rows[0][column("index")], "0");
+        assert_eq!(&rows[0][column("road")], "1 main st");
+        assert_eq!(&rows[0][column("error")], "");
+        assert_eq!(&rows[1][column("index")], "2");
+        assert_eq!(&rows[1][column("road")], "");
+        assert!(rows[1][column("error")].starts_with("Invalid JSON line"));
+    }
+
+    #[tokio::test]
+    async fn normalize_writes_every_format() {
+        let text = "1 Main St\n\n2 High St\n";
+
+        let results: Vec<serde_json::Value> =
+            serde_json::from_str(&normalize_output(text, OutputFormat::Json).await).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1]["index"], 2);
+        assert_eq!(results[1]["expansions"][0], "2 high st");
+
+        let results = ndjson(&normalize_output(text, OutputFormat::Ndjson).await);
+        assert_eq!(results[0]["expansion_count"], 1);
+
+        let csv = normalize_output(text, OutputFormat::Csv).await;
+        assert_eq!(
+            csv,
+            "index,original,expansion_count,expansions,error\n\
+             0,1 Main St,1,1 main st,\n\
+             2,2 High St,1,2 high st,\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn normalize_reports_invalid_rows() {
+        let results =
+            ndjson(&normalize_output("1 Main St\nbad\0address\n", OutputFormat::Ndjson).await);
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0]["error"].is_null());
+        assert_eq!(results[1]["error"]["code"], "INVALID_INPUT");
+        assert!(results[1]["expansions"].is_null());
+        assert!(results[1]["expansion_count"].is_null());
+    }
+}
@@ -0,0 +1,578 @@
+use anyhow::{bail, Context};
+use flate2::read::GzDecoder;
+use libpostal_rs::{data::DataManager, LibPostal, LibPostalConfig};
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek},
+    path::{Path, PathBuf},
+};
+
+use libpostal_api::{
+    config::Config,
+    models::{CanaryReport, DataIntegrityStatus, DataVersion},
+    services::{canary::run_canaries, libpostal::read_data_version, LibPostalService},
+};
+
+use crate::cli::DataCommand;
+
+/// Files libpostal needs to load a model, relative to the data directory
+///
+/// Listed so `data verify` can show the state of each one; whether the data
+/// passes is decided by the same integrity check the server runs.
+const DATA_FILES: &[&str] = &[
+    "address_expansions/address_dictionary.dat",
+    "numex/numex.dat",
+    "transliteration/transliteration.dat",
+    "address_parser/address_parser_crf.dat",
+    "address_parser/address_parser_phrases.dat",
+    "address_parser/address_parser_postal_codes.dat",
+    "address_parser/address_parser_vocab.trie",
+    "language_classifier/language_classifier.dat",
+];
+
+/// Top-level directories of a data directory, one per libpostal module
+const MODULE_DIRS: &[&str] = &[
+    "address_expansions",
+    "numex",
+    "transliteration",
+    "address_parser",
+    "language_classifier",
+];
+
+/// Environment variable libpostal-rs reads its data directory from
+pub const DATA_DIR_ENV: &str = "LIBPOSTAL_DATA_DIR";
+
+/// Signature at the start of libpostal's numex table
+const NUMEX_TABLE_SIGNATURE: u32 = 0xBBBB_BBBB;
+
+/// Result of `data verify`
+#[derive(Debug, Serialize)]
+struct VerifyReport {
+    data_dir: String,
+    passed: bool,
+    /// Outcome of the integrity check the server runs when loading the data
+    data_integrity: DataIntegrityStatus,
+    files: Vec<DataFileStatus>,
+    data_version: DataVersion,
+    /// Present when `--canaries` was given and the data passed the integrity check
+    canary: Option<CanaryReport>,
+}
+
+/// State of one required data file
+#[derive(Debug, Serialize)]
+struct DataFileStatus {
+    path: String,
+    /// "ok", "missing", "empty" or "unreadable"
+    status: &'static str,
+    size_bytes: Option<u64>,
+    error: Option<String>,
+}
+
+/// Result of `data info`
+#[derive(Debug, Serialize)]
+struct InfoReport {
+    data_dir: String,
+    available: bool,
+    data_version: DataVersion,
+    size_bytes: u64,
+    modules: Vec<ModuleSize>,
+    /// Languages with number rules in the data, when they could be read
+    languages: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+struct ModuleSize {
+    name: &'static str,
+    size_bytes: u64,
+}
+
+/// Run a `data` subcommand against the configured data directory
+pub async fn run(command: DataCommand, config: &Config) -> anyhow::Result<()> {
+    let data_dir = &config.data.dir;
+    match command {
+        DataCommand::Verify { canaries, json } => verify(data_dir, canaries, json).await,
+        DataCommand::Info { json } => info(data_dir, json),
+        DataCommand::Install { archives, force } => install(data_dir, &archives, force),
+    }
+}
+
+/// Load the model from `data_dir` and parse the canary addresses with it
+///
+/// libpostal-rs sets itself up from the directory in [`DATA_DIR_ENV`], not the
+/// config, so `main` must have set it to `data_dir` before starting the runtime.
+pub async fn load_and_run_canaries(data_dir: &Path) -> anyhow::Result<CanaryReport> {
+    if std::env::var_os(DATA_DIR_ENV).as_deref() != Some(data_dir.as_os_str()) {
+        bail!("{DATA_DIR_ENV} must be set to {}", data_dir.display());
+    }
+
+    let config = LibPostalConfig::builder()
+        .data_dir(data_dir)
+        .auto_download_data(false)
+        .verify_data_integrity(false)
+        .build();
+    let postal = LibPostal::with_config(config)
+        .await
+        .with_context(|| format!("Unable to load LibPostal data from {}", data_dir.display()))?;
+
+    Ok(
+        tokio::task::spawn_blocking(move || run_canaries(|address| postal.parse_address(address)))
+            .await?,
+    )
+}
+
+async fn verify(data_dir: &Path, canaries: bool, json: bool) -> anyhow::Result<()> {
+    let files: Vec<DataFileStatus> = DATA_FILES
+        .iter()
+        .map(|file| check_file(data_dir, file))
+        .collect();
+    let data_integrity = LibPostalService::check_data_integrity(data_dir, true).await;
+    let intact = data_integrity.status == "verified";
+
+    let canary = if canaries && intact {
+        Some(load_and_run_canaries(data_dir).await?)
+    } else {
+        None
+    };
+
+    let report = VerifyReport {
+        data_dir: data_dir.display().to_string(),
+        passed: intact && canary.as_ref().is_none_or(|canary| canary.passed),
+        data_integrity,
+        files,
+        data_version: read_data_version(data_dir),
+        canary,
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("Data directory: {}", report.data_dir);
+        for file in &report.files {
+            let detail = match (&file.error, file.size_bytes) {
+                (Some(error), _) => error.clone(),
+                (None, Some(size)) => format_size(size),
+                (None, None) => String::new(),
+            };
+            println!("  {:<10} {:<50} {detail}", file.status, file.path);
+        }
+        match &report.data_integrity.message {
+            Some(message) => println!("Integrity:    failed ({message})"),
+            None => println!("Integrity:    {}", report.data_integrity.status),
+        }
+        println!("Data version: {}", describe_version(&report.data_version));
+        if let Some(canary) = &report.canary {
+            for check in &canary.checks {
+                let outcome = if check.passed { "ok" } else { "failed" };
+                println!("  canary {outcome:<6} {}", check.address);
+                for problem in check.problems.iter().chain(&check.error) {
+                    println!("           {problem}");
+                }
+            }
+        }
+    }
+
+    if !report.passed {
+        bail!(
+            "LibPostal data in {} failed verification",
+            data_dir.display()
+        );
+    }
+    if !json {
+        println!("LibPostal data verified");
+    }
+    Ok(())
+}
+
+fn info(data_dir: &Path, json: bool) -> anyhow::Result<()> {
+    let manager = DataManager::with_data_dir(data_dir);
+    let modules: Vec<ModuleSize> = MODULE_DIRS
+        .iter()
+        .map(|&name| ModuleSize {
+            name,
+            size_bytes: dir_size(&data_dir.join(name)),
+        })
+        .collect();
+
+    let report = InfoReport {
+        data_dir: data_dir.display().to_string(),
+        available: manager.is_data_available(),
+        data_version: read_data_version(data_dir),
+        size_bytes: manager.data_size().unwrap_or_default(),
+        modules,
+        languages: numex_languages(&data_dir.join("numex/numex.dat")),
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("Data directory: {}", report.data_dir);
+    println!(
+        "Available:      {}",
+        if report.available {
+            "yes"
+        } else {
+            "no (files missing)"
+        }
+    );
+    println!("Data version:   {}", describe_version(&report.data_version));
+    println!("Total size:     {}", format_size(report.size_bytes));
+    for module in &report.modules {
+        println!("  {:<20} {}", module.name, format_size(module.size_bytes));
+    }
+    match &report.languages {
+        Some(languages) => println!(
+            "Languages:      {} ({})",
+            languages.len(),
+            languages.join(", ")
+        ),
+        None => println!("Languages:      unknown"),
+    }
+    Ok(())
+}
+
+/// Unpack local archives and move the result into `data_dir`
+///
+/// Everything is unpacked next to the data directory first and checked before
+/// the directory is touched, so a bad archive never leaves it half replaced.
+fn install(data_dir: &Path, archives: &[PathBuf], force: bool) -> anyhow::Result<()> {
+    let has_data = std::fs::read_dir(data_dir)
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false);
+    if has_data && !force {
+        bail!(
+            "{} already contains files; pass --force to replace them",
+            data_dir.display()
+        );
+    }
+
+    let staging = sibling(data_dir, "installing");
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging)
+            .with_context(|| format!("Unable to remove {}", staging.display()))?;
+    }
+    std::fs::create_dir_all(&staging)
+        .with_context(|| format!("Unable to create {}", staging.display()))?;
+
+    let installed = unpack_all(archives, &staging).and_then(|()| {
+        let root = data_root(&staging).with_context(|| {
+            format!(
+                "The archives contain no LibPostal data directories ({})",
+                MODULE_DIRS.join(", ")
+            )
+        })?;
+        DataManager::with_data_dir(&root)
+            .verify_data()
+            .context("The archives do not contain a complete LibPostal data set")?;
+        Ok(root)
+    });
+    let root = match installed {
+        Ok(root) => root,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+
+    let previous = sibling(data_dir, "previous");
+    if has_data {
+        if previous.exists() {
+            std::fs::remove_dir_all(&previous)
+                .with_context(|| format!("Unable to remove {}", previous.display()))?;
+        }
+        std::fs::rename(data_dir, &previous)
+            .with_context(|| format!("Unable to move {} out of the way", data_dir.display()))?;
+    } else if data_dir.is_dir() {
+        std::fs::remove_dir(data_dir)
+            .with_context(|| format!("Unable to replace {}", data_dir.display()))?;
+    }
+    if let Err(e) = std::fs::rename(&root, data_dir) {
+        // Put the old data back so a failed install leaves the server runnable
+        if has_data {
+            if let Err(restore) = std::fs::rename(&previous, data_dir) {
+                bail!(
+                    "Unable to move the data into {}: {e}; the previous data is still in {} \
+                     and could not be restored: {restore}",
+                    data_dir.display(),
+                    previous.display()
+                );
+            }
+        }
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e)
+            .with_context(|| format!("Unable to move the data into {}", data_dir.display()));
+    }
+    if has_data {
+        println!("Previous data kept in {}", previous.display());
+    }
+    if staging.exists() {
+        let _ = std::fs::remove_dir_all(&staging);
+    }
+
+    println!(
+        "Installed LibPostal data into {} ({})",
+        data_dir.display(),
+        format_size(
+            DataManager::with_data_dir(data_dir)
+                .data_size()
+                .unwrap_or_default()
+        )
+    );
+    Ok(())
+}
+
+/// Unpack each archive into `dest`, decompressing gzip archives
+fn unpack_all(archives: &[PathBuf], dest: &Path) -> anyhow::Result<()> {
+    for archive in archives {
+        let mut file = BufReader::new(
+            File::open(archive).with_context(|| format!("Unable to open {}", archive.display()))?,
+        );
+
+        let mut magic = [0u8; 2];
+        let gzipped = file.read_exact(&mut magic).is_ok() && magic == [0x1f, 0x8b];
+        file.rewind()?;
+
+        println!("Unpacking {}", archive.display());
+        let reader: Box<dyn Read> = if gzipped {
+            Box::new(GzDecoder::new(file))
+        } else {
+            Box::new(file)
+        };
+        tar::Archive::new(reader)
+            .unpack(dest)
+            .with_context(|| format!("Unable to unpack {}", archive.display()))?;
+    }
+    Ok(())
+}
+
+/// The directory holding the module directories: `dir` itself, or its only subdirectory
+fn data_root(dir: &Path) -> Option<PathBuf> {
+    let is_root = |dir: &Path| MODULE_DIRS.iter().any(|module| dir.join(module).is_dir());
+    if is_root(dir) {
+        return Some(dir.to_path_buf());
+    }
+
+    let mut entries = std::fs::read_dir(dir).ok()?.flatten();
+    let only = entries.next()?.path();
+    (entries.next().is_none() && only.is_dir() && is_root(&only)).then_some(only)
+}
+
+fn check_file(data_dir: &Path, file: &str) -> DataFileStatus {
+    let (status, size_bytes, error) = match std::fs::metadata(data_dir.join(file)) {
+        Ok(metadata) if metadata.len() == 0 => ("empty", Some(0), None),
+        Ok(metadata) => ("ok", Some(metadata.len()), None),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => ("missing", None, None),
+        Err(e) => ("unreadable", None, Some(e.to_string())),
+    };
+
+    DataFileStatus {
+        path: file.to_string(),
+        status,
+        size_bytes,
+        error,
+    }
+}
+
+/// Languages named in libpostal's numex table, or `None` if it cannot be read
+///
+/// The table starts with its signature and the number of languages, followed
+/// by each language's NUL-terminated name and rule counts, all big-endian.
+fn numex_languages(path: &Path) -> Option<Vec<String>> {
+    fn read_u64(file: &mut impl Read) -> Option<u64> {
+        let mut bytes = [0u8; 8];
+        file.read_exact(&mut bytes).ok()?;
+        Some(u64::from_be_bytes(bytes))
+    }
+
+    let mut file = BufReader::new(File::open(path).ok()?);
+
+    let mut signature = [0u8; 4];
+    file.read_exact(&mut signature).ok()?;
+    if u32::from_be_bytes(signature) != NUMEX_TABLE_SIGNATURE {
+        return None;
+    }
+
+    let count = read_u64(&mut file)?;
+    if count > 1000 {
+        return None;
+    }
+
+    let mut languages = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let len = read_u64(&mut file)?;
+        if !(2..=32).contains(&len) {
+            return None;
+        }
+        let mut name = vec![0u8; len as usize];
+        file.read_exact(&mut name).ok()?;
+        if name.pop() != Some(0) || !name.iter().all(u8::is_ascii_graphic) {
+            return None;
+        }
+        languages.push(String::from_utf8(name).ok()?);
+
+        // whole_tokens_only flag, rule count and ordinal suffix count
+        let mut flag = [0u8; 1];
+        file.read_exact(&mut flag).ok()?;
+        read_u64(&mut file)?;
+        read_u64(&mut file)?;
+    }
+
+    languages.sort();
+    Some(languages)
+}
+
+/// A path next to `dir`, named after it with the given suffix
+fn sibling(dir: &Path, suffix: &str) -> PathBuf {
+    let name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "data".to_string());
+    dir.with_file_name(format!("{name}.{suffix}"))
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn describe_version(version: &DataVersion) -> String {
+    let part = |name: &str, value: &Option<String>| {
+        format!("{name} {}", value.as_deref().unwrap_or("unknown"))
+    };
+    [
+        part("base", &version.base),
+        part("parser", &version.parser),
+        part("language classifier", &version.language_classifier),
+    ]
+    .join(", ")
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Temporary directory removed when dropped
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("libpostal-api-data-{}", uuid::Uuid::new_v4()));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn numex_table(signature: u32, languages: &[&str]) -> Vec<u8> {
+        let mut bytes = signature.to_be_bytes().to_vec();
+        bytes.extend((languages.len() as u64).to_be_bytes());
+        for language in languages {
+            bytes.extend((language.len() as u64 + 1).to_be_bytes());
+            bytes.extend(language.as_bytes());
+            bytes.push(0);
+            bytes.push(1);
+            bytes.extend(12u64.to_be_bytes());
+            bytes.extend(0u64.to_be_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn numex_languages_are_read_sorted() {
+        let dir = TempDir::new();
+        let path = dir.0.join("numex.dat");
+        std::fs::write(
+            &path,
+            numex_table(NUMEX_TABLE_SIGNATURE, &["fr", "de", "en"]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            numex_languages(&path),
+            Some(vec!["de".to_string(), "en".to_string(), "fr".to_string()])
+        );
+    }
+
+    #[test]
+    fn numex_languages_rejects_other_files() {
+        let dir = TempDir::new();
+        let path = dir.0.join("numex.dat");
+
+        std::fs::write(&path, numex_table(0xAAAAAAAA, &["en"])).unwrap();
+        assert_eq!(numex_languages(&path), None);
+
+        let mut truncated = numex_table(NUMEX_TABLE_SIGNATURE, &["en", "fr"]);
+        truncated.truncate(truncated.len() - 4);
+        std::fs::write(&path, truncated).unwrap();
+        assert_eq!(numex_languages(&path), None);
+
+        assert_eq!(numex_languages(&dir.0.join("missing.dat")), None);
+    }
+
+    #[test]
+    fn data_root_finds_the_data_or_its_single_subdirectory() {
+        let dir = TempDir::new();
+        assert_eq!(data_root(&dir.0), None);
+
+        let nested = dir.0.join("libpostal");
+        std::fs::create_dir_all(nested.join("numex")).unwrap();
+        assert_eq!(data_root(&dir.0), Some(nested.clone()));
+        assert_eq!(data_root(&nested), Some(nested.clone()));
+
+        std::fs::create_dir(dir.0.join("other")).unwrap();
+        assert_eq!(data_root(&dir.0), None);
+    }
+
+    #[test]
+    fn sibling_appends_a_suffix_to_the_directory_name() {
+        assert_eq!(
+            sibling(Path::new("/var/lib/libpostal"), "previous"),
+            PathBuf::from("/var/lib/libpostal.previous")
+        );
+        assert_eq!(
+            sibling(Path::new("/"), "installing"),
+            PathBuf::from("/data.installing")
+        );
+    }
+
+    #[test]
+    fn sizes_use_binary_units() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(2 * 1024 * 1024 * 1024), "2.0 GiB");
+        assert_eq!(format_size(u64::MAX), "16777216.0 TiB");
+    }
+}
@@ -0,0 +1,137 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+use libpostal_api::config::ConfigOverrides;
+
+pub mod address;
+pub mod data;
+
+#[derive(Parser)]
+#[command(name = "libpostal-api")]
+#[command(about = "LibPostal API server", long_about = None)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Initialize LibPostal only (don't start the server)
+    #[arg(
+        long,
+        help = "Initialize LibPostal and exit without starting the server"
+    )]
+    pub init_only: bool,
+
+    /// Print the resolved configuration as TOML, with secrets redacted, and exit
+    #[arg(long)]
+    pub print_config: bool,
+
+    /// Load the model from a data directory, print the canary parse report as JSON and exit
+    ///
+    /// Run by the server to check new data for a reload without disturbing its own model.
+    #[arg(long, value_name = "DIR", hide = true)]
+    pub verify_data: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub config: ConfigOverrides,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Start the HTTP server (the default when no command is given)
+    Serve,
+
+    /// Parse addresses, one per line, without starting the server
+    Parse(ParseArgs),
+
+    /// Normalize addresses, one per line, without starting the server
+    Normalize(NormalizeArgs),
+
+    /// Inspect and install LibPostal data without network access
+    #[command(subcommand)]
+    Data(DataCommand),
+}
+
+/// Where addresses are read from and how results are written
+#[derive(clap::Args)]
+pub struct InputArgs {
+    /// File with one address per line; standard input when omitted or `-`
+    pub file: Option<PathBuf>,
+
+    /// How results are written to standard output
+    #[arg(long, value_enum, default_value_t = OutputFormat::Ndjson)]
+    pub output: OutputFormat,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    /// One JSON array of results
+    Json,
+    /// One JSON result per line
+    Ndjson,
+    /// A header row, then one row per result
+    Csv,
+}
+
+#[derive(clap::Args)]
+pub struct ParseArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Language hint (ISO 639-1 code) for lines that give none
+    #[arg(long)]
+    pub language: Option<String>,
+
+    /// Country hint (ISO 3166-1 alpha-2 code) for lines that give none
+    #[arg(long)]
+    pub country: Option<String>,
+}
+
+#[derive(clap::Args)]
+pub struct NormalizeArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Normalization level: "light", "medium" or "aggressive"
+    #[arg(long)]
+    pub level: Option<String>,
+
+    /// Language hints (ISO 639-1 codes), comma-separated
+    #[arg(long = "language", value_delimiter = ',')]
+    pub languages: Option<Vec<String>>,
+}
+
+/// Data management commands; they work on the configured data directory
+#[derive(Subcommand)]
+pub enum DataCommand {
+    /// Check that every data file is present and non-empty
+    Verify {
+        /// Also load the model and parse the canary addresses with it
+        #[arg(long)]
+        canaries: bool,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print the data version, size and languages
+    Info {
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Install data from local archives, for hosts without network access
+    ///
+    /// Accepts the archives published by libpostal (libpostal_data.tar.gz,
+    /// parser.tar.gz and language_classifier.tar.gz) or one archive of a whole
+    /// data directory, gzip-compressed or not.
+    Install {
+        /// Archive to install; repeat for each archive
+        #[arg(long = "from", value_name = "ARCHIVE", required = true)]
+        archives: Vec<PathBuf>,
+
+        /// Replace data already in the directory, keeping it as `<dir>.previous`
+        #[arg(long)]
+        force: bool,
+    },
+}
@@ -0,0 +1,6 @@
+pub mod settings;
+
+pub use settings::{
+    CacheConfig, Config, ConfigOverrides, DataConfig, ErrorFormat, JobsConfig, ValidationConfig,
+    WorkerConfig,
+};
@@ -0,0 +1,641 @@
+use anyhow::{bail, Context};
+use axum::http::HeaderValue;
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+
+use crate::middleware::rate_limit::{parse_trusted_proxy, RateLimitConfig};
+
+pub use libpostal_api_models::ValidationConfig;
+
+/// Resolved server configuration
+///
+/// Values are layered with the following precedence (highest first):
+/// command line arguments, environment variables, the TOML configuration
+/// file, and finally the built-in defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// HTTP server settings
+    pub server: ServerConfig,
+    /// LibPostal data settings
+    pub data: DataConfig,
+    /// LibPostal worker pool settings
+    pub workers: WorkerConfig,
+    /// Health reporting settings
+    pub health: HealthConfig,
+    /// Result cache settings
+    pub cache: CacheConfig,
+    /// Bulk file job settings
+    pub jobs: JobsConfig,
+    /// Per-IP rate limiting settings
+    pub rate_limit: RateLimitConfig,
+    /// API key authentication settings
+    pub auth: AuthConfig,
+    /// Request validation limits
+    pub validation: ValidationConfig,
+    /// Admin endpoint settings
+    pub admin: AdminConfig,
+}
+
+/// HTTP server settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    /// Address and port to listen on
+    pub bind_address: SocketAddr,
+    /// Origins allowed to make cross-origin requests ("*" allows any origin)
+    pub cors_origins: Vec<String>,
+    /// Maximum request body size in bytes
+    pub body_limit_bytes: usize,
+    /// Seconds clients and CDNs may cache GET parse and normalize responses
+    pub cache_max_age_secs: u64,
+    /// Error body sent to clients that do not ask for `application/problem+json`
+    pub error_format: ErrorFormat,
+}
+
+/// Body format of error responses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorFormat {
+    /// `{"success": false, "error": {...}}` JSON envelope
+    #[default]
+    Envelope,
+    /// RFC 7807 problem details
+    Problem,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind_address: SocketAddr::from(([0, 0, 0, 0], 3000)),
+            cors_origins: vec![
+                "https://libpostal.pendejo.dev".to_string(),
+                "http://localhost:3000".to_string(),
+            ],
+            body_limit_bytes: 1024 * 1024,
+            cache_max_age_secs: 3600,
+            error_format: ErrorFormat::default(),
+        }
+    }
+}
+
+/// LibPostal data settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DataConfig {
+    /// Directory holding the LibPostal data files
+    pub dir: PathBuf,
+    /// Download the data files when they are missing
+    pub auto_download: bool,
+    /// Verify the integrity of the data files on startup
+    pub verify_integrity: bool,
+}
+
+impl Default for DataConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("./data"),
+            auto_download: true,
+            verify_integrity: true,
+        }
+    }
+}
+
+/// LibPostal worker pool settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkerConfig {
+    /// Number of blocking workers running libpostal calls (0 uses one per CPU core)
+    pub threads: usize,
+    /// Maximum number of requests waiting for a free worker
+    pub queue_depth: usize,
+}
+
+impl Default for WorkerConfig {
+    fn default() -> Self {
+        Self {
+            threads: 0,
+            queue_depth: 1024,
+        }
+    }
+}
+
+impl WorkerConfig {
+    /// Number of workers to start, resolving 0 to the available parallelism
+    pub fn resolved_workers(&self) -> usize {
+        if self.threads > 0 {
+            return self.threads;
+        }
+
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    }
+}
+
+/// Health reporting settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthConfig {
+    /// Seconds between background self-test parses reported by the readiness probe
+    pub self_test_interval_secs: u64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            self_test_interval_secs: 30,
+        }
+    }
+}
+
+/// Result cache settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    /// Maximum number of cached results per operation (0 disables the cache)
+    pub capacity: usize,
+    /// Seconds a cached result stays valid
+    pub ttl_secs: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 10_000,
+            ttl_secs: 3600,
+        }
+    }
+}
+
+/// Bulk file job settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JobsConfig {
+    /// Directory holding uploaded files, results and job state
+    pub dir: PathBuf,
+    /// Maximum size of an uploaded file in bytes
+    pub max_upload_bytes: usize,
+    /// Number of jobs processed at the same time
+    pub concurrency: usize,
+    /// Seconds a finished job and its files are kept (0 keeps them forever)
+    pub retention_secs: u64,
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("./jobs"),
+            max_upload_bytes: 512 * 1024 * 1024,
+            concurrency: 1,
+            retention_secs: 7 * 24 * 60 * 60,
+        }
+    }
+}
+
+/// API key authentication settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    /// TOML file listing the accepted API keys (unset leaves the API open)
+    pub keys_file: Option<PathBuf>,
+}
+
+/// Admin endpoint settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminConfig {
+    /// Bearer token required by the admin endpoints (unset disables them)
+    pub token: Option<String>,
+}
+
+/// Configuration values supplied on the command line or through the environment
+///
+/// Every option is global, so it may be given before or after a subcommand.
+#[derive(Debug, Default, clap::Args)]
+#[command(next_help_heading = "Configuration")]
+pub struct ConfigOverrides {
+    /// Path to a TOML configuration file
+    #[arg(long, global = true, env = "LIBPOSTAL_API_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// Address and port to listen on
+    #[arg(long, global = true, env = "LIBPOSTAL_API_BIND")]
+    pub bind: Option<SocketAddr>,
+
+    /// Directory holding the LibPostal data files
+    #[arg(long, global = true, env = "LIBPOSTAL_DATA_DIR")]
+    pub data_dir: Option<PathBuf>,
+
+    /// Download the data files when they are missing
+    #[arg(long, global = true, env = "LIBPOSTAL_AUTO_DOWNLOAD")]
+    pub auto_download: Option<bool>,
+
+    /// Verify the integrity of the data files on startup
+    #[arg(long, global = true, env = "LIBPOSTAL_VERIFY_INTEGRITY")]
+    pub verify_integrity: Option<bool>,
+
+    /// Origins allowed to make cross-origin requests
+    #[arg(
+        long = "cors-origin",
+        global = true,
+        env = "LIBPOSTAL_API_CORS_ORIGINS",
+        value_delimiter = ','
+    )]
+    pub cors_origins: Option<Vec<String>>,
+
+    /// Maximum request body size in bytes
+    #[arg(long, global = true, env = "LIBPOSTAL_API_BODY_LIMIT")]
+    pub body_limit: Option<usize>,
+
+    /// Seconds clients and CDNs may cache GET parse and normalize responses
+    #[arg(long, global = true, env = "LIBPOSTAL_API_CACHE_MAX_AGE")]
+    pub cache_max_age: Option<u64>,
+
+    /// Error body sent to clients that do not ask for problem details
+    #[arg(long, global = true, value_enum, env = "LIBPOSTAL_API_ERROR_FORMAT")]
+    pub error_format: Option<ErrorFormat>,
+
+    /// Number of blocking workers running libpostal calls (0 uses one per CPU core)
+    #[arg(long, global = true, env = "LIBPOSTAL_API_WORKERS")]
+    pub workers: Option<usize>,
+
+    /// Maximum number of requests waiting for a free worker
+    #[arg(long, global = true, env = "LIBPOSTAL_API_QUEUE_DEPTH")]
+    pub queue_depth: Option<usize>,
+
+    /// Seconds between background self-tests reported by the readiness probe
+    #[arg(long, global = true, env = "LIBPOSTAL_API_SELF_TEST_INTERVAL")]
+    pub self_test_interval: Option<u64>,
+
+    /// Maximum number of cached results per operation (0 disables the cache)
+    #[arg(long, global = true, env = "LIBPOSTAL_API_CACHE_CAPACITY")]
+    pub cache_capacity: Option<usize>,
+
+    /// Seconds a cached result stays valid
+    #[arg(long, global = true, env = "LIBPOSTAL_API_CACHE_TTL")]
+    pub cache_ttl: Option<u64>,
+
+    /// Directory holding uploaded files, results and job state
+    #[arg(long, global = true, env = "LIBPOSTAL_API_JOBS_DIR")]
+    pub jobs_dir: Option<PathBuf>,
+
+    /// Maximum size of an uploaded job file in bytes
+    #[arg(long, global = true, env = "LIBPOSTAL_API_JOBS_MAX_UPLOAD")]
+    pub jobs_max_upload: Option<usize>,
+
+    /// Number of jobs processed at the same time
+    #[arg(long, global = true, env = "LIBPOSTAL_API_JOBS_CONCURRENCY")]
+    pub jobs_concurrency: Option<usize>,
+
+    /// Seconds a finished job and its files are kept (0 keeps them forever)
+    #[arg(long, global = true, env = "LIBPOSTAL_API_JOBS_RETENTION")]
+    pub jobs_retention: Option<u64>,
+
+    /// TOML file listing the accepted API keys
+    #[arg(long, global = true, env = "LIBPOSTAL_API_KEYS_FILE")]
+    pub api_keys_file: Option<PathBuf>,
+
+    /// Bearer token required by the admin endpoints
+    #[arg(
+        long,
+        global = true,
+        env = "LIBPOSTAL_API_ADMIN_TOKEN",
+        hide_env_values = true
+    )]
+    pub admin_token: Option<String>,
+
+    /// Maximum length of an address in characters
+    #[arg(long, global = true, env = "LIBPOSTAL_API_MAX_ADDRESS_LENGTH")]
+    pub max_address_length: Option<usize>,
+
+    /// Maximum number of language hints in a single request
+    #[arg(long, global = true, env = "LIBPOSTAL_API_MAX_LANGUAGES")]
+    pub max_languages: Option<usize>,
+
+    /// Maximum number of addresses in a single batch request
+    #[arg(long, global = true, env = "LIBPOSTAL_API_MAX_BATCH_SIZE")]
+    pub max_batch_size: Option<usize>,
+
+    /// Maximum number of addresses compared in a single match request
+    #[arg(long, global = true, env = "LIBPOSTAL_API_MAX_MATCH_ADDRESSES")]
+    pub max_match_addresses: Option<usize>,
+
+    /// Sustained requests per second allowed for each client IP
+    #[arg(long, global = true, env = "RATE_LIMIT_PER_SECOND")]
+    pub rate_limit_per_second: Option<u32>,
+
+    /// Requests a client may send at once before being throttled
+    #[arg(long, global = true, env = "RATE_LIMIT_BURST")]
+    pub rate_limit_burst: Option<u32>,
+
+    /// Proxies (IPs or CIDR ranges) whose X-Forwarded-For header is trusted
+    #[arg(
+        long = "trusted-proxy",
+        global = true,
+        env = "RATE_LIMIT_TRUSTED_PROXIES",
+        value_delimiter = ',',
+        value_parser = parse_trusted_proxy
+    )]
+    pub trusted_proxies: Option<Vec<IpNet>>,
+}
+
+impl Config {
+    /// Resolve the configuration from the optional file and the given overrides
+    pub fn load(overrides: &ConfigOverrides) -> anyhow::Result<Self> {
+        let mut config = match &overrides.config {
+            Some(path) => Self::from_file(path)?,
+            None => Self::default(),
+        };
+
+        config.apply_overrides(overrides);
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Read a configuration file, using defaults for any missing values
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read config file {}", path.display()))?;
+
+        toml::from_str(&contents).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    /// Render the configuration as TOML
+    pub fn to_toml(&self) -> anyhow::Result<String> {
+        toml::to_string_pretty(self).context("Unable to serialize configuration")
+    }
+
+    /// A copy that is safe to show over the API, with secrets replaced
+    pub fn redacted(&self) -> Self {
+        let mut config = self.clone();
+        if config.admin.token.is_some() {
+            config.admin.token = Some("[redacted]".to_string());
+        }
+        config
+    }
+
+    fn apply_overrides(&mut self, overrides: &ConfigOverrides) {
+        if let Some(bind) = overrides.bind {
+            self.server.bind_address = bind;
+        }
+        if let Some(ref origins) = overrides.cors_origins {
+            self.server.cors_origins = origins.clone();
+        }
+        if let Some(limit) = overrides.body_limit {
+            self.server.body_limit_bytes = limit;
+        }
+        if let Some(max_age) = overrides.cache_max_age {
+            self.server.cache_max_age_secs = max_age;
+        }
+        if let Some(format) = overrides.error_format {
+            self.server.error_format = format;
+        }
+        if let Some(ref dir) = overrides.data_dir {
+            self.data.dir = dir.clone();
+        }
+        if let Some(auto_download) = overrides.auto_download {
+            self.data.auto_download = auto_download;
+        }
+        if let Some(verify) = overrides.verify_integrity {
+            self.data.verify_integrity = verify;
+        }
+        if let Some(workers) = overrides.workers {
+            self.workers.threads = workers;
+        }
+        if let Some(depth) = overrides.queue_depth {
+            self.workers.queue_depth = depth;
+        }
+        if let Some(interval) = overrides.self_test_interval {
+            self.health.self_test_interval_secs = interval;
+        }
+        if let Some(capacity) = overrides.cache_capacity {
+            self.cache.capacity = capacity;
+        }
+        if let Some(ttl) = overrides.cache_ttl {
+            self.cache.ttl_secs = ttl;
+        }
+        if let Some(ref dir) = overrides.jobs_dir {
+            self.jobs.dir = dir.clone();
+        }
+        if let Some(max_upload) = overrides.jobs_max_upload {
+            self.jobs.max_upload_bytes = max_upload;
+        }
+        if let Some(concurrency) = overrides.jobs_concurrency {
+            self.jobs.concurrency = concurrency;
+        }
+        if let Some(retention) = overrides.jobs_retention {
+            self.jobs.retention_secs = retention;
+        }
+        if let Some(ref path) = overrides.api_keys_file {
+            self.auth.keys_file = Some(path.clone());
+        }
+        if let Some(ref token) = overrides.admin_token {
+            self.admin.token = Some(token.clone());
+        }
+        if let Some(length) = overrides.max_address_length {
+            self.validation.max_address_length = length;
+        }
+        if let Some(languages) = overrides.max_languages {
+            self.validation.max_languages = languages;
+        }
+        if let Some(batch_size) = overrides.max_batch_size {
+            self.validation.max_batch_size = batch_size;
+        }
+        if let Some(match_addresses) = overrides.max_match_addresses {
+            self.validation.max_match_addresses = match_addresses;
+        }
+        if let Some(per_second) = overrides.rate_limit_per_second {
+            self.rate_limit.requests_per_second = per_second;
+        }
+        if let Some(burst) = overrides.rate_limit_burst {
+            self.rate_limit.burst_size = burst;
+        }
+        if let Some(ref proxies) = overrides.trusted_proxies {
+            self.rate_limit.trusted_proxies = proxies.clone();
+        }
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        for origin in &self.server.cors_origins {
+            if origin != "*" && origin.parse::<HeaderValue>().is_err() {
+                bail!("Invalid CORS origin '{origin}'");
+            }
+        }
+
+        if self.server.body_limit_bytes == 0 {
+            bail!("Body limit must be greater than zero");
+        }
+
+        if self.validation.max_address_length == 0 {
+            bail!("Maximum address length must be greater than zero");
+        }
+
+        if self.validation.max_batch_size == 0 {
+            bail!("Maximum batch size must be greater than zero");
+        }
+
+        if self.validation.max_match_addresses < 2 {
+            bail!("Maximum match addresses must be at least two");
+        }
+
+        if self.health.self_test_interval_secs == 0 {
+            bail!("Self-test interval must be greater than zero");
+        }
+
+        if self.cache.capacity > 0 && self.cache.ttl_secs == 0 {
+            bail!("Cache TTL must be greater than zero when the cache is enabled");
+        }
+
+        if self.jobs.max_upload_bytes == 0 || self.jobs.concurrency == 0 {
+            bail!("Job upload limit and concurrency must be greater than zero");
+        }
+
+        if self
+            .admin
+            .token
+            .as_deref()
+            .is_some_and(|token| token.trim().is_empty())
+        {
+            bail!("Admin token must not be empty");
+        }
+
+        if self.rate_limit.requests_per_second == 0 || self.rate_limit.burst_size == 0 {
+            bail!("Rate limit quotas must be greater than zero");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use uuid::Uuid;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        config: ConfigOverrides,
+    }
+
+    /// Overrides as clap resolves them from `args` and the environment
+    fn overrides(args: &[&str]) -> ConfigOverrides {
+        Cli::try_parse_from(std::iter::once("libpostal-api").chain(args.iter().copied()))
+            .unwrap()
+            .config
+    }
+
+    /// Temporary config file removed when dropped
+    struct ConfigFile(PathBuf);
+
+    impl ConfigFile {
+        fn new(contents: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("libpostal-api-config-{}.toml", Uuid::new_v4()));
+            std::fs::write(&path, contents).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for ConfigFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn arguments_beat_environment_beats_file() {
+        let file = ConfigFile::new(
+            "[validation]\nmax_batch_size = 10\nmax_languages = 3\nmax_address_length = 50\n",
+        );
+        let path = file.0.to_str().unwrap();
+
+        // Only this test reads these variables
+        std::env::set_var("LIBPOSTAL_API_MAX_LANGUAGES", "4");
+        std::env::set_var("LIBPOSTAL_API_MAX_ADDRESS_LENGTH", "60");
+        let parsed = overrides(&["--config", path, "--max-address-length", "70"]);
+        std::env::remove_var("LIBPOSTAL_API_MAX_LANGUAGES");
+        std::env::remove_var("LIBPOSTAL_API_MAX_ADDRESS_LENGTH");
+
+        let config = Config::load(&parsed).unwrap();
+        assert_eq!(config.validation.max_batch_size, 10);
+        assert_eq!(config.validation.max_languages, 4);
+        assert_eq!(config.validation.max_address_length, 70);
+        // Untouched values keep their defaults
+        assert_eq!(
+            config.server.body_limit_bytes,
+            Config::default().server.body_limit_bytes
+        );
+    }
+
+    #[test]
+    fn overrides_replace_every_layer() {
+        let mut config = Config::default();
+        config.apply_overrides(&ConfigOverrides {
+            bind: Some("127.0.0.1:8080".parse().unwrap()),
+            cors_origins: Some(vec!["https://example.com".to_string()]),
+            admin_token: Some("secret".to_string()),
+            jobs_retention: Some(0),
+            trusted_proxies: Some(vec!["10.0.0.0/8".parse().unwrap()]),
+            ..Default::default()
+        });
+
+        assert_eq!(config.server.bind_address.to_string(), "127.0.0.1:8080");
+        assert_eq!(config.server.cors_origins, ["https://example.com"]);
+        assert_eq!(config.admin.token.as_deref(), Some("secret"));
+        assert_eq!(config.jobs.retention_secs, 0);
+        assert_eq!(config.rate_limit.trusted_proxies.len(), 1);
+    }
+
+    #[test]
+    fn unknown_file_keys_are_rejected() {
+        let file = ConfigFile::new("[server]\nbind = \"0.0.0.0:3000\"\n");
+        assert!(Config::from_file(&file.0).is_err());
+    }
+
+    #[test]
+    fn validate_rejects_unusable_values() {
+        type Breakage = fn(&mut Config);
+        let cases: [(&str, Breakage); 10] = [
+            ("CORS origin", |c| {
+                c.server.cors_origins = vec!["bad\norigin".to_string()]
+            }),
+            ("Body limit", |c| c.server.body_limit_bytes = 0),
+            ("address length", |c| c.validation.max_address_length = 0),
+            ("batch size", |c| c.validation.max_batch_size = 0),
+            ("match addresses", |c| c.validation.max_match_addresses = 1),
+            ("Self-test interval", |c| {
+                c.health.self_test_interval_secs = 0
+            }),
+            ("Cache TTL", |c| c.cache.ttl_secs = 0),
+            ("Job upload", |c| c.jobs.concurrency = 0),
+            ("Admin token", |c| c.admin.token = Some("  ".to_string())),
+            ("Rate limit", |c| c.rate_limit.burst_size = 0),
+        ];
+
+        assert!(Config::default().validate().is_ok());
+        for (message, break_config) in cases {
+            let mut config = Config::default();
+            break_config(&mut config);
+            let err = config.validate().unwrap_err().to_string();
+            assert!(err.contains(message), "{err}");
+        }
+    }
+
+    #[test]
+    fn redacted_hides_the_admin_token() {
+        let mut config = Config::default();
+        config.admin.token = Some("secret".to_string());
+
+        let toml = config.redacted().to_toml().unwrap();
+        assert!(!toml.contains("secret"));
+        assert!(toml.contains("[redacted]"));
+    }
+}
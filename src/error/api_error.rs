@@ -1,25 +1,59 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
-use serde_json::json;
-use std::fmt;
+use std::{fmt, time::Duration};
 use tracing::error;
 
+use crate::{
+    models::{
+        BatchItemError, ErrorCode, ErrorDetail, ErrorResponse, FieldError, ProblemDetails,
+        ValidationErrors,
+    },
+    services::metrics::Metrics,
+    util::ceil_secs,
+};
+
 /// Custom error type for the API
 #[derive(Debug)]
 pub enum ApiError {
-    /// LibPostal related errors
+    /// LibPostal related errors, reported by kind; see [`ApiError::status_code`]
     LibPostal(libpostal_rs::Error),
     /// Invalid input data
     InvalidInput(String),
+    /// Request fields that failed validation
+    Validation(ValidationErrors),
+    /// Request body exceeds the size limit
+    PayloadTooLarge(String),
     /// Rate limit exceeded
-    RateLimitExceeded,
+    RateLimitExceeded {
+        /// Sustained requests per second allowed per IP
+        limit: u32,
+        /// Time until the client may retry
+        retry_after: Duration,
+    },
+    /// Missing or invalid credentials
+    Unauthorized(String),
+    /// An API key exceeded its own rate or daily quota
+    QuotaExceeded {
+        /// Which limit was exceeded
+        message: String,
+        /// Time until the key may retry
+        retry_after: Duration,
+    },
+    /// Requested resource does not exist
+    NotFound(String),
+    /// Resource is not in a state that allows the request
+    Conflict(String),
     /// Internal server error
     Internal(String),
     /// Service unavailable
     ServiceUnavailable(String),
+    /// Maintenance mode is on; carries the operator's message
+    Maintenance(String),
+    /// All workers are busy and the wait queue is full
+    QueueFull,
 }
 
 impl fmt::Display for ApiError {
@@ -27,9 +61,17 @@ impl fmt::Display for ApiError {
         match self {
             ApiError::LibPostal(err) => write!(f, "LibPostal error: {err}"),
             ApiError::InvalidInput(msg) => write!(f, "Invalid input: {msg}"),
-            ApiError::RateLimitExceeded => write!(f, "Rate limit exceeded"),
+            ApiError::Validation(errors) => write!(f, "Invalid input: {errors}"),
+            ApiError::PayloadTooLarge(msg) => write!(f, "Payload too large: {msg}"),
+            ApiError::RateLimitExceeded { .. } => write!(f, "Rate limit exceeded"),
+            ApiError::Unauthorized(msg) => write!(f, "Unauthorized: {msg}"),
+            ApiError::QuotaExceeded { message, .. } => write!(f, "Quota exceeded: {message}"),
+            ApiError::NotFound(msg) => write!(f, "Not found: {msg}"),
+            ApiError::Conflict(msg) => write!(f, "Conflict: {msg}"),
             ApiError::Internal(msg) => write!(f, "Internal error: {msg}"),
             ApiError::ServiceUnavailable(msg) => write!(f, "Service unavailable: {msg}"),
+            ApiError::Maintenance(msg) => write!(f, "Maintenance: {msg}"),
+            ApiError::QueueFull => write!(f, "Worker queue is full"),
         }
     }
 }
@@ -42,31 +84,105 @@ impl From<libpostal_rs::Error> for ApiError {
     }
 }
 
+impl From<ValidationErrors> for ApiError {
+    fn from(errors: ValidationErrors) -> Self {
+        ApiError::Validation(errors)
+    }
+}
+
+impl From<&ApiError> for BatchItemError {
+    fn from(err: &ApiError) -> Self {
+        Self {
+            code: err.error_code(),
+            message: err.public_message(),
+        }
+    }
+}
+
+impl ApiError {
+    /// HTTP status code returned for this error
+    ///
+    /// libpostal errors get `422` when the address could not be processed,
+    /// `503` when the model or its data is unusable, and `500` otherwise.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::LibPostal(err) => match libpostal_error_code(err) {
+                ErrorCode::UnprocessableAddress => StatusCode::UNPROCESSABLE_ENTITY,
+                ErrorCode::ModelNotLoaded | ErrorCode::ModelDataError => {
+                    StatusCode::SERVICE_UNAVAILABLE
+                }
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            },
+            ApiError::InvalidInput(_) | ApiError::Validation(_) => StatusCode::BAD_REQUEST,
+            ApiError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::RateLimitExceeded { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::QuotaExceeded { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Maintenance(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::QueueFull => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    /// Stable machine-readable error code
+    pub fn code(&self) -> &'static str {
+        self.error_code().as_str()
+    }
+
+    /// Stable machine-readable error code, as the shared enum
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            ApiError::LibPostal(err) => libpostal_error_code(err),
+            ApiError::InvalidInput(_) | ApiError::Validation(_) => ErrorCode::InvalidInput,
+            ApiError::PayloadTooLarge(_) => ErrorCode::PayloadTooLarge,
+            ApiError::RateLimitExceeded { .. } => ErrorCode::RateLimitExceeded,
+            ApiError::Unauthorized(_) => ErrorCode::Unauthorized,
+            ApiError::QuotaExceeded { .. } => ErrorCode::QuotaExceeded,
+            ApiError::NotFound(_) => ErrorCode::NotFound,
+            ApiError::Conflict(_) => ErrorCode::Conflict,
+            ApiError::Internal(_) => ErrorCode::InternalError,
+            ApiError::ServiceUnavailable(_) => ErrorCode::ServiceUnavailable,
+            ApiError::Maintenance(_) => ErrorCode::Maintenance,
+            ApiError::QueueFull => ErrorCode::QueueFull,
+        }
+    }
+
+    /// Message that is safe to show to API clients
+    pub fn public_message(&self) -> String {
+        match self {
+            ApiError::LibPostal(err) => match libpostal_error_code(err) {
+                ErrorCode::UnprocessableAddress => "libpostal could not process this address",
+                ErrorCode::ModelNotLoaded => "The address model is not loaded",
+                ErrorCode::ModelDataError => "The address model data is unavailable",
+                _ => "Address processing failed",
+            }
+            .to_string(),
+            ApiError::InvalidInput(msg) => msg.clone(),
+            ApiError::Validation(errors) => errors.to_string(),
+            ApiError::PayloadTooLarge(msg) => msg.clone(),
+            ApiError::RateLimitExceeded { limit, .. } => {
+                format!("Rate limit exceeded. Maximum {limit} requests per second per IP")
+            }
+            ApiError::Unauthorized(msg) => msg.clone(),
+            ApiError::QuotaExceeded { message, .. } => message.clone(),
+            ApiError::NotFound(msg) => msg.clone(),
+            ApiError::Conflict(msg) => msg.clone(),
+            ApiError::Internal(_) => "Internal server error".to_string(),
+            ApiError::ServiceUnavailable(_) => "Service temporarily unavailable".to_string(),
+            ApiError::Maintenance(msg) => msg.clone(),
+            ApiError::QueueFull => "Server is at capacity, please retry shortly".to_string(),
+        }
+    }
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let (status, error_message, error_code) = match &self {
-            ApiError::LibPostal(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Address processing failed",
-                "LIBPOSTAL_ERROR",
-            ),
-            ApiError::InvalidInput(msg) => (StatusCode::BAD_REQUEST, msg.as_str(), "INVALID_INPUT"),
-            ApiError::RateLimitExceeded => (
-                StatusCode::TOO_MANY_REQUESTS,
-                "Rate limit exceeded. Maximum 10 requests per second per IP",
-                "RATE_LIMIT_EXCEEDED",
-            ),
-            ApiError::Internal(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Internal server error",
-                "INTERNAL_ERROR",
-            ),
-            ApiError::ServiceUnavailable(_) => (
-                StatusCode::SERVICE_UNAVAILABLE,
-                "Service temporarily unavailable",
-                "SERVICE_UNAVAILABLE",
-            ),
-        };
+        let status = self.status_code();
+        let error_message = self.public_message();
+        let error_code = self.code();
 
         // Log the error for debugging
         error!(
@@ -74,17 +190,92 @@ impl IntoResponse for ApiError {
             status_code = %status,
             "API error occurred"
         );
+        Metrics::global().record_error(error_code);
 
-        let body = Json(json!({
-            "success": false,
-            "error": {
-                "code": error_code,
-                "message": error_message,
-                "timestamp": chrono::Utc::now().to_rfc3339()
-            }
-        }));
+        let report = ErrorReport {
+            status,
+            code: self.error_code(),
+            detail: error_message.clone(),
+            errors: match &self {
+                ApiError::Validation(errors) => errors.errors().to_vec(),
+                _ => Vec::new(),
+            },
+        };
+
+        let body = Json(ErrorResponse {
+            success: false,
+            error: ErrorDetail {
+                code: self.error_code(),
+                message: error_message,
+                timestamp: chrono::Utc::now().to_rfc3339(),
+            },
+        });
+
+        let mut response = (status, body).into_response();
+        response.extensions_mut().insert(report);
+
+        let retry_after = match &self {
+            ApiError::RateLimitExceeded { retry_after, .. }
+            | ApiError::QuotaExceeded { retry_after, .. } => Some(ceil_secs(*retry_after).max(1)),
+            ApiError::QueueFull => Some(1),
+            _ => None,
+        };
+
+        if let Some(seconds) = retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+        }
+
+        if let ApiError::Unauthorized(_) = self {
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        }
 
-        (status, body).into_response()
+        response
+    }
+}
+
+/// Error code of a libpostal failure, telling bad input from model problems and bugs
+fn libpostal_error_code(err: &libpostal_rs::Error) -> ErrorCode {
+    use libpostal_rs::Error;
+
+    match err {
+        Error::ParseError { .. } | Error::NormalizationError { .. } => {
+            ErrorCode::UnprocessableAddress
+        }
+        Error::InitializationFailed { .. } => ErrorCode::ModelNotLoaded,
+        Error::DataError { .. } | Error::IoError { .. } | Error::NetworkError { .. } => {
+            ErrorCode::ModelDataError
+        }
+        // Input that libpostal cannot take is rejected by validation first
+        Error::FfiError { .. } => ErrorCode::LibpostalError,
+    }
+}
+
+/// An error response's details, kept in its extensions so that
+/// [`crate::middleware::error_format_middleware`] can send them as problem details
+#[derive(Debug, Clone)]
+pub struct ErrorReport {
+    status: StatusCode,
+    code: ErrorCode,
+    detail: String,
+    errors: Vec<FieldError>,
+}
+
+impl ErrorReport {
+    /// The problem details of the error, for the request with ID `instance`
+    pub fn into_problem(self, instance: Option<String>) -> ProblemDetails {
+        ProblemDetails {
+            problem_type: self.code.problem_type(),
+            title: self.code.title().to_string(),
+            status: self.status.as_u16(),
+            detail: self.detail,
+            instance,
+            code: self.code,
+            errors: self.errors,
+        }
     }
 }
 
@@ -1,3 +1,3 @@
 pub mod api_error;
 
-pub use api_error::{ApiError, ApiResult};
+pub use api_error::{ApiError, ApiResult, ErrorReport};
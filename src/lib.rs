@@ -0,0 +1,68 @@
+//! LibPostal address parsing and normalization over HTTP
+//!
+//! [`router`] serves the address endpoints so they can be mounted in any axum
+//! application; [`server::serve`] runs the complete standalone server.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//!
+//! use libpostal_api::{AppState, Config, LibPostalService};
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let config = Arc::new(Config::default());
+//! LibPostalService::initialize(&config.data, &config.workers, &config.cache).await?;
+//!
+//! let app = axum::Router::new().nest(
+//!     "/address",
+//!     libpostal_api::router(AppState::libpostal(config)),
+//! );
+//! # let _: axum::Router = app;
+//! # Ok(())
+//! # }
+//! ```
+
+use axum::{extract::DefaultBodyLimit, middleware::from_fn, Extension, Router};
+use tower_http::limit::RequestBodyLimitLayer;
+
+pub mod api;
+pub mod config;
+pub mod error;
+pub mod middleware;
+pub mod server;
+pub mod services;
+pub mod util;
+
+pub use libpostal_api_models as models;
+
+pub use api::{handlers::ApiDoc, AppState};
+pub use config::Config;
+pub use error::{ApiError, ApiResult};
+pub use services::{engine::AddressEngine, LibPostalService};
+
+/// The address endpoints, relative to wherever they are mounted
+///
+/// Serves parse, normalize, format, match, their batch and stream variants,
+/// key usage and health, with the body limits and validation of the state's
+/// configuration. Responses carry an `x-request-id` header, kept from an outer
+/// [`middleware::request_id_middleware`] when there is one.
+///
+/// Rate limiting, API keys and maintenance mode are left to the caller; see
+/// [`server::serve`] for how the standalone server layers them. Bulk jobs run
+/// on the global LibPostal model rather than the state's engine, so only the
+/// standalone server serves them. The probes and metrics of
+/// [`api::create_monitoring_routes`] take the same state and can be mounted
+/// alongside.
+pub fn router(state: AppState) -> Router {
+    let config = state.config().clone();
+
+    api::create_api_routes()
+        .layer(RequestBodyLimitLayer::new(config.server.body_limit_bytes))
+        // Streams are only limited per line, by the handler
+        .merge(api::create_stream_routes())
+        .with_state(state)
+        .layer(from_fn(middleware::error_format_middleware))
+        .layer(from_fn(middleware::request_id_middleware))
+        // Body size limits are applied per route group above
+        .layer(DefaultBodyLimit::disable())
+        .layer(Extension(config))
+}
@@ -1,38 +1,70 @@
-use anyhow::Context;
-use axum::{middleware::from_fn, Extension, Router};
 use clap::Parser;
-use std::net::SocketAddr;
-use tower::ServiceBuilder;
-use tower_http::{services::ServeDir, trace::TraceLayer};
+use libpostal_api::{
+    config::Config,
+    services::{health, LibPostalService},
+};
+use std::{path::Path, sync::Arc};
 use tracing::{error, info};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use utoipa::OpenApi;
 
-mod api;
-mod error;
-mod middleware;
-mod models;
-mod services;
+mod cli;
 
-use services::LibPostalService;
+use cli::{Args, Command};
 
-#[derive(Parser)]
-#[command(name = "libpostal-api")]
-#[command(about = "LibPostal API server")]
-struct Args {
-    /// Initialize LibPostal only (don't start the server)
-    #[arg(
-        long,
-        help = "Initialize LibPostal and exit without starting the server"
-    )]
-    init_only: bool,
-}
-
-#[tokio::main]
-async fn main() -> Result<(), anyhow::Error> {
+fn main() -> Result<(), anyhow::Error> {
     // Parse command line arguments
     let args = Args::parse();
 
+    // libpostal-rs sets the model up from the directory named in the
+    // environment, so point it at the one in use. This happens before the
+    // runtime starts any threads, as changing the environment is not thread safe.
+    if let Some(data_dir) = args.verify_data {
+        std::env::set_var(cli::data::DATA_DIR_ENV, &data_dir);
+        return tokio::runtime::Runtime::new()?.block_on(verify_data(&data_dir));
+    }
+
+    // Resolve configuration from the config file, environment and arguments
+    let config = Arc::new(Config::load(&args.config)?);
+    std::env::set_var(cli::data::DATA_DIR_ENV, &config.data.dir);
+
+    tokio::runtime::Runtime::new()?.block_on(run(args, config))
+}
+
+/// Load the model from `data_dir`, print the canary report as JSON and exit
+async fn verify_data(data_dir: &Path) -> Result<(), anyhow::Error> {
+    match cli::data::load_and_run_canaries(data_dir).await {
+        Ok(report) => {
+            println!("{}", serde_json::to_string(&report)?);
+            if report.passed {
+                return Ok(());
+            }
+            eprintln!("The canary parses failed");
+        }
+        // A single line, so the reloading server can report it
+        Err(e) => eprintln!("{e:#}"),
+    }
+    std::process::exit(1);
+}
+
+async fn run(args: Args, config: Arc<Config>) -> Result<(), anyhow::Error> {
+    match args.command {
+        Some(Command::Data(command)) => return cli::data::run(command, &config).await,
+        Some(Command::Parse(parse_args)) => {
+            init_cli_tracing();
+            return cli::address::parse(parse_args, &config).await;
+        }
+        Some(Command::Normalize(normalize_args)) => {
+            init_cli_tracing();
+            return cli::address::normalize(normalize_args, &config).await;
+        }
+        Some(Command::Serve) | None => {}
+    }
+
+    if args.print_config {
+        print!("{}", config.redacted().to_toml()?);
+        return Ok(());
+    }
+
     // Initialize tracing
     tracing_subscriber::registry()
         .with(
@@ -41,10 +73,12 @@ async fn main() -> Result<(), anyhow::Error> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    health::mark_started();
     info!("Starting LibPostal API server...");
 
     // Initialize LibPostal service
-    if let Err(e) = LibPostalService::initialize().await {
+    if let Err(e) = LibPostalService::initialize(&config.data, &config.workers, &config.cache).await
+    {
         error!("Failed to initialize LibPostal service: {}", e);
         return Err(anyhow::anyhow!(
             "Failed to initialize LibPostal service: {}",
@@ -58,37 +92,15 @@ async fn main() -> Result<(), anyhow::Error> {
         return Ok(());
     }
 
-    // Build the application
-    let app = Router::new()
-        // API routes
-        .nest("/api/v1", api::create_api_routes())
-        // Documentation routes - merge SwaggerUi directly
-        .merge(
-            utoipa_swagger_ui::SwaggerUi::new("/docs")
-                .url("/api-docs/openapi.json", api::handlers::ApiDoc::openapi()),
-        )
-        // Static file serving for the landing page
-        .fallback_service(ServeDir::new("static"))
-        .layer(
-            ServiceBuilder::new()
-                .layer(TraceLayer::new_for_http())
-                .layer(middleware::create_cors_layer())
-                .layer(from_fn(middleware::request_id_middleware)),
-        );
-
-    // Start the server
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
-    info!("LibPostal API server listening on {}", addr);
-    info!("API documentation available at: http://localhost:3000/docs");
-    info!("Landing page available at: http://localhost:3000");
-
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(
-        listener,
-        app.into_make_service_with_connect_info::<SocketAddr>(),
-    )
-    .await
-    .context("Unable to serve application")?;
+    libpostal_api::server::serve(config).await
+}
 
-    Ok(())
+/// Log warnings and errors to standard error, leaving standard output to the results
+fn init_cli_tracing() {
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| "warn".into()),
+        )
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .init();
 }
@@ -0,0 +1,36 @@
+use axum::{
+    extract::{Request, State},
+    http::header,
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+use crate::error::{ApiError, ApiResult};
+
+/// Require `Authorization: Bearer <token>` matching the configured admin token
+pub async fn admin_auth_middleware(
+    State(token): State<Arc<str>>,
+    req: Request,
+    next: Next,
+) -> ApiResult<Response> {
+    let provided = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+
+    match provided {
+        Some(provided) if constant_time_eq(provided.as_bytes(), token.as_bytes()) => {
+            Ok(next.run(req).await)
+        }
+        Some(_) => Err(ApiError::Unauthorized("Invalid admin token".to_string())),
+        None => Err(ApiError::Unauthorized("Admin token required".to_string())),
+    }
+}
+
+/// Compare secrets without short-circuiting on the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,468 @@
+use anyhow::{bail, Context};
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderName},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use governor::{
+    clock::{Clock, DefaultClock},
+    DefaultDirectRateLimiter, Quota,
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    num::NonZeroU32,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    error::{ApiError, ApiResult},
+    models::ApiKeyUsage,
+};
+
+pub const X_API_KEY: HeaderName = HeaderName::from_static("x-api-key");
+
+/// Contents of the API keys file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeysFile {
+    #[serde(default)]
+    keys: Vec<KeyEntry>,
+}
+
+/// One `[[keys]]` entry of the API keys file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeyEntry {
+    /// Name used in logs and usage reports
+    name: String,
+    /// Hex-encoded SHA-256 digest of the key, so the file holds no usable secrets
+    key_sha256: String,
+    /// Sustained requests per second allowed for the key (unset: no per-key limit)
+    requests_per_second: Option<u32>,
+    /// Requests the key may send at once (defaults to `requests_per_second`)
+    burst_size: Option<u32>,
+    /// Requests allowed per UTC day (unset: unlimited)
+    daily_quota: Option<u64>,
+}
+
+/// An accepted API key with its limits and usage counters
+pub struct ApiKey {
+    name: String,
+    requests_per_second: Option<u32>,
+    daily_quota: Option<u64>,
+    limiter: Option<DefaultDirectRateLimiter>,
+    usage: Mutex<UsageCounters>,
+}
+
+#[derive(Default)]
+struct UsageCounters {
+    day: Option<NaiveDate>,
+    requests_today: u64,
+    total_requests: u64,
+    rejected_requests: u64,
+    last_used_at: Option<DateTime<Utc>>,
+}
+
+/// The request was authenticated with this key
+#[derive(Clone)]
+pub struct AuthenticatedKey(pub Arc<ApiKey>);
+
+/// API keys loaded from the keys file, looked up by the digest of the presented key
+pub struct ApiKeyStore {
+    keys: Vec<Arc<ApiKey>>,
+    by_digest: HashMap<[u8; 32], usize>,
+}
+
+impl ApiKeyStore {
+    /// Load the keys file
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read API keys file {}", path.display()))?;
+        let file: KeysFile = toml::from_str(&contents)
+            .with_context(|| format!("Invalid API keys file {}", path.display()))?;
+
+        Self::from_entries(file.keys)
+            .with_context(|| format!("Invalid API keys file {}", path.display()))
+    }
+
+    fn from_entries(entries: Vec<KeyEntry>) -> anyhow::Result<Self> {
+        if entries.is_empty() {
+            bail!("At least one key is required");
+        }
+
+        let mut keys = Vec::with_capacity(entries.len());
+        let mut by_digest = HashMap::with_capacity(entries.len());
+
+        for entry in entries {
+            let name = entry.name.trim().to_string();
+            if name.is_empty() {
+                bail!("Key names must not be empty");
+            }
+            if keys.iter().any(|key: &Arc<ApiKey>| key.name == name) {
+                bail!("Duplicate key name '{name}'");
+            }
+
+            let digest = parse_digest(&entry.key_sha256).with_context(|| {
+                format!("Key '{name}' must have a key_sha256 of 64 hex characters")
+            })?;
+            if by_digest.insert(digest, keys.len()).is_some() {
+                bail!("Key '{name}' has the same digest as another key");
+            }
+
+            if entry.daily_quota == Some(0) {
+                bail!("Key '{name}' must have a daily quota greater than zero");
+            }
+
+            let limiter = match entry.requests_per_second {
+                Some(per_second) => {
+                    let per_second = NonZeroU32::new(per_second).with_context(|| {
+                        format!("Key '{name}' must allow more than zero requests per second")
+                    })?;
+                    let burst = NonZeroU32::new(entry.burst_size.unwrap_or(per_second.get()))
+                        .with_context(|| {
+                            format!("Key '{name}' must have a burst size greater than zero")
+                        })?;
+                    Some(DefaultDirectRateLimiter::direct(
+                        Quota::per_second(per_second).allow_burst(burst),
+                    ))
+                }
+                None if entry.burst_size.is_some() => {
+                    bail!("Key '{name}' sets a burst size without requests_per_second")
+                }
+                None => None,
+            };
+
+            keys.push(Arc::new(ApiKey {
+                name,
+                requests_per_second: entry.requests_per_second,
+                daily_quota: entry.daily_quota,
+                limiter,
+                usage: Mutex::new(UsageCounters::default()),
+            }));
+        }
+
+        Ok(Self { keys, by_digest })
+    }
+
+    /// Number of configured keys
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Whether no keys are configured
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Find the key matching a presented secret
+    pub fn authenticate(&self, provided: &str) -> Option<&Arc<ApiKey>> {
+        let digest: [u8; 32] = Sha256::digest(provided.as_bytes()).into();
+        self.by_digest.get(&digest).map(|&index| &self.keys[index])
+    }
+
+    /// Usage of every key, in keys file order
+    pub fn usage(&self) -> Vec<ApiKeyUsage> {
+        self.keys.iter().map(|key| key.usage()).collect()
+    }
+}
+
+impl ApiKey {
+    /// Count a request against the key's daily quota and rate
+    ///
+    /// Refused requests are counted as rejected and do not use up the quota.
+    pub fn admit(&self) -> ApiResult<()> {
+        let now = Utc::now();
+        let mut usage = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        usage.roll_over(now.date_naive());
+
+        if let Some(quota) = self.daily_quota {
+            if usage.requests_today >= quota {
+                usage.rejected_requests += 1;
+                return Err(ApiError::QuotaExceeded {
+                    message: format!("Daily quota of {quota} requests exceeded for this API key"),
+                    retry_after: (next_reset(now) - now).to_std().unwrap_or_default(),
+                });
+            }
+        }
+
+        if let (Some(limiter), Some(per_second)) = (&self.limiter, self.requests_per_second) {
+            if let Err(not_until) = limiter.check() {
+                usage.rejected_requests += 1;
+                return Err(ApiError::QuotaExceeded {
+                    message: format!(
+                        "Rate limit exceeded. Maximum {per_second} requests per second for this API key"
+                    ),
+                    retry_after: not_until.wait_time_from(DefaultClock::default().now()),
+                });
+            }
+        }
+
+        usage.requests_today += 1;
+        usage.total_requests += 1;
+        usage.last_used_at = Some(now);
+        Ok(())
+    }
+
+    /// Current limits and usage of the key
+    pub fn usage(&self) -> ApiKeyUsage {
+        let now = Utc::now();
+        let mut usage = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        usage.roll_over(now.date_naive());
+
+        ApiKeyUsage {
+            name: self.name.clone(),
+            requests_per_second: self.requests_per_second,
+            daily_quota: self.daily_quota,
+            requests_today: usage.requests_today,
+            remaining_today: self
+                .daily_quota
+                .map(|quota| quota.saturating_sub(usage.requests_today)),
+            quota_resets_at: next_reset(now).to_rfc3339(),
+            total_requests: usage.total_requests,
+            rejected_requests: usage.rejected_requests,
+            last_used_at: usage.last_used_at.map(|at| at.to_rfc3339()),
+        }
+    }
+}
+
+impl UsageCounters {
+    /// Start a new daily count when the UTC date has changed
+    fn roll_over(&mut self, today: NaiveDate) {
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.requests_today = 0;
+        }
+    }
+}
+
+/// Require a known API key and count the request against its limits
+///
+/// The key is accepted as `Authorization: Bearer <key>` or `X-API-Key: <key>`.
+pub async fn api_key_middleware(
+    State(store): State<Arc<ApiKeyStore>>,
+    mut req: Request,
+    next: Next,
+) -> ApiResult<Response> {
+    let Some(provided) = presented_key(req.headers()) else {
+        return Err(ApiError::Unauthorized("API key required".to_string()));
+    };
+    let key = store
+        .authenticate(provided)
+        .ok_or_else(|| ApiError::Unauthorized("Invalid API key".to_string()))?
+        .clone();
+
+    key.admit()?;
+
+    req.extensions_mut().insert(AuthenticatedKey(key));
+    Ok(next.run(req).await)
+}
+
+/// The key from `X-API-Key` or a bearer `Authorization` header
+fn presented_key(headers: &HeaderMap) -> Option<&str> {
+    let key = match headers.get(X_API_KEY) {
+        Some(value) => value.to_str().ok()?,
+        None => headers
+            .get(header::AUTHORIZATION)?
+            .to_str()
+            .ok()?
+            .strip_prefix("Bearer ")?,
+    };
+
+    Some(key.trim()).filter(|key| !key.is_empty())
+}
+
+/// Start of the next UTC day, when daily quotas reset
+fn next_reset(now: DateTime<Utc>) -> DateTime<Utc> {
+    now.date_naive()
+        .checked_add_days(Days::new(1))
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .map(|midnight| midnight.and_utc())
+        .unwrap_or(now)
+}
+
+/// Decode a hex-encoded SHA-256 digest
+fn parse_digest(hex: &str) -> Option<[u8; 32]> {
+    let hex = hex.trim();
+    if hex.len() != 64 || !hex.is_ascii() {
+        return None;
+    }
+
+    let mut digest = [0u8; 32];
+    for (byte, pair) in digest.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        let pair = std::str::from_utf8(pair).ok()?;
+        *byte = u8::from_str_radix(pair, 16).ok()?;
+    }
+    Some(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn sha256_hex(key: &str) -> String {
+        Sha256::digest(key.as_bytes())
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    fn entry(name: &str, key: &str) -> KeyEntry {
+        KeyEntry {
+            name: name.to_string(),
+            key_sha256: sha256_hex(key),
+            requests_per_second: None,
+            burst_size: None,
+            daily_quota: None,
+        }
+    }
+
+    fn store_error(entries: Vec<KeyEntry>) -> String {
+        format!("{:#}", ApiKeyStore::from_entries(entries).err().unwrap())
+    }
+
+    #[test]
+    fn keys_are_found_by_the_digest_of_the_secret() {
+        let store =
+            ApiKeyStore::from_entries(vec![entry("billing", "s3cret"), entry("search", "other")])
+                .unwrap();
+
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.authenticate("s3cret").unwrap().name, "billing");
+        assert_eq!(store.authenticate("other").unwrap().name, "search");
+        assert!(store.authenticate("S3CRET").is_none());
+        assert_eq!(
+            store
+                .usage()
+                .iter()
+                .map(|key| key.name.as_str())
+                .collect::<Vec<_>>(),
+            ["billing", "search"]
+        );
+    }
+
+    #[test]
+    fn invalid_key_files_are_rejected() {
+        assert!(store_error(Vec::new()).contains("At least one key"));
+        assert!(store_error(vec![entry(" ", "a")]).contains("must not be empty"));
+        assert!(store_error(vec![entry("a", "x"), entry("a", "y")]).contains("Duplicate"));
+        assert!(store_error(vec![entry("a", "x"), entry("b", "x")]).contains("same digest"));
+
+        let mut bad_digest = entry("a", "x");
+        bad_digest.key_sha256 = "abc".to_string();
+        assert!(store_error(vec![bad_digest]).contains("64 hex characters"));
+
+        let mut zero_quota = entry("a", "x");
+        zero_quota.daily_quota = Some(0);
+        assert!(store_error(vec![zero_quota]).contains("daily quota"));
+
+        let mut zero_rate = entry("a", "x");
+        zero_rate.requests_per_second = Some(0);
+        assert!(store_error(vec![zero_rate]).contains("more than zero requests"));
+
+        let mut burst_only = entry("a", "x");
+        burst_only.burst_size = Some(5);
+        assert!(store_error(vec![burst_only]).contains("without requests_per_second"));
+    }
+
+    #[test]
+    fn daily_quota_refuses_requests_until_midnight() {
+        let mut limited = entry("a", "x");
+        limited.daily_quota = Some(2);
+        let store = ApiKeyStore::from_entries(vec![limited]).unwrap();
+        let key = store.authenticate("x").unwrap();
+
+        key.admit().unwrap();
+        key.admit().unwrap();
+        match key.admit() {
+            Err(ApiError::QuotaExceeded { retry_after, .. }) => {
+                assert!(retry_after <= std::time::Duration::from_secs(24 * 60 * 60));
+            }
+            other => panic!("expected a quota error, got {other:?}"),
+        }
+
+        let usage = key.usage();
+        assert_eq!(usage.requests_today, 2);
+        assert_eq!(usage.remaining_today, Some(0));
+        assert_eq!(usage.total_requests, 2);
+        assert_eq!(usage.rejected_requests, 1);
+        assert!(usage.last_used_at.is_some());
+    }
+
+    #[test]
+    fn per_key_rate_allows_the_burst_only() {
+        let mut limited = entry("a", "x");
+        limited.requests_per_second = Some(1);
+        limited.burst_size = Some(2);
+        let store = ApiKeyStore::from_entries(vec![limited]).unwrap();
+        let key = store.authenticate("x").unwrap();
+
+        key.admit().unwrap();
+        key.admit().unwrap();
+        assert!(matches!(key.admit(), Err(ApiError::QuotaExceeded { .. })));
+
+        let usage = key.usage();
+        assert_eq!((usage.total_requests, usage.rejected_requests), (2, 1));
+        assert_eq!(usage.remaining_today, None);
+    }
+
+    #[test]
+    fn keys_are_taken_from_either_header() {
+        let headers = |pairs: &[(HeaderName, &'static str)]| {
+            let mut headers = HeaderMap::new();
+            for (name, value) in pairs {
+                headers.insert(name.clone(), HeaderValue::from_static(value));
+            }
+            headers
+        };
+
+        let bearer = headers(&[(header::AUTHORIZATION, "Bearer  s3cret ")]);
+        assert_eq!(presented_key(&bearer), Some("s3cret"));
+
+        let both = headers(&[
+            (X_API_KEY, "from-header"),
+            (header::AUTHORIZATION, "Bearer other"),
+        ]);
+        assert_eq!(presented_key(&both), Some("from-header"));
+
+        assert_eq!(
+            presented_key(&headers(&[(header::AUTHORIZATION, "Basic abc")])),
+            None
+        );
+        assert_eq!(presented_key(&headers(&[(X_API_KEY, "  ")])), None);
+        assert_eq!(presented_key(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn digests_must_be_64_hex_characters() {
+        let digest = parse_digest(&sha256_hex("x")).unwrap();
+        assert_eq!(digest, <[u8; 32]>::from(Sha256::digest(b"x")));
+        assert_eq!(
+            parse_digest(&format!(" {} ", sha256_hex("x").to_uppercase())),
+            Some(digest)
+        );
+
+        assert_eq!(parse_digest(&sha256_hex("x")[..62]), None);
+        assert_eq!(parse_digest(&"g".repeat(64)), None);
+        assert_eq!(parse_digest(&format!("{}é", "a".repeat(62))), None);
+    }
+
+    #[test]
+    fn quotas_reset_at_the_next_utc_midnight() {
+        let afternoon = Utc.with_ymd_and_hms(2026, 10, 18, 13, 45, 0).unwrap();
+        let midnight = Utc.with_ymd_and_hms(2026, 10, 19, 0, 0, 0).unwrap();
+
+        assert_eq!(next_reset(afternoon), midnight);
+        assert_eq!(
+            next_reset(midnight),
+            Utc.with_ymd_and_hms(2026, 10, 20, 0, 0, 0).unwrap()
+        );
+    }
+}
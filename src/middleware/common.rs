@@ -1,10 +1,17 @@
-use axum::{extract::Request, middleware::Next, response::Response};
-use tower_http::cors::{Any, CorsLayer};
+use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 use uuid::Uuid;
 
+use super::rate_limit::{X_RATELIMIT_LIMIT, X_RATELIMIT_REMAINING, X_RATELIMIT_RESET};
+
 /// Add request ID to headers
+///
+/// A request that already has an ID, from this middleware further out, keeps it.
 pub async fn request_id_middleware(mut req: Request, next: Next) -> Response {
-    let request_id = Uuid::new_v4().to_string();
+    let request_id = match req.extensions().get::<String>() {
+        Some(request_id) => request_id.clone(),
+        None => Uuid::new_v4().to_string(),
+    };
 
     // Store request ID in extensions for handlers to access
     req.extensions_mut().insert(request_id.clone());
@@ -19,14 +26,29 @@ pub async fn request_id_middleware(mut req: Request, next: Next) -> Response {
     response
 }
 
-/// Create CORS layer for libpostal.pendejo.dev
-pub fn create_cors_layer() -> CorsLayer {
+/// Create CORS layer for the configured origins ("*" allows any origin)
+pub fn create_cors_layer(origins: &[String]) -> CorsLayer {
+    let allow_origin = if origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        // Origins are validated when the configuration is loaded
+        AllowOrigin::list(
+            origins
+                .iter()
+                .filter_map(|origin| origin.parse::<HeaderValue>().ok()),
+        )
+    };
+
     CorsLayer::new()
-        .allow_origin([
-            "https://libpostal.pendejo.dev".parse().unwrap(),
-            "http://localhost:3000".parse().unwrap(), // For development
-        ])
+        .allow_origin(allow_origin)
         .allow_methods(Any)
         .allow_headers(Any)
-        .expose_headers([axum::http::HeaderName::from_static("x-request-id")])
+        .expose_headers([
+            axum::http::HeaderName::from_static("x-request-id"),
+            axum::http::header::RETRY_AFTER,
+            axum::http::header::ETAG,
+            X_RATELIMIT_LIMIT,
+            X_RATELIMIT_REMAINING,
+            X_RATELIMIT_RESET,
+        ])
 }
@@ -0,0 +1,108 @@
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+use crate::{
+    config::{Config, ErrorFormat},
+    error::ErrorReport,
+    models::PROBLEM_JSON,
+};
+
+/// Send error responses as problem details when the client or configuration asks
+///
+/// Errors are sent as problem details when the `Accept` header lists
+/// `application/problem+json`, or when the configured error format is
+/// `problem`. Must run inside [`super::request_id_middleware`], whose ID becomes
+/// the problem's `instance`.
+pub async fn error_format_middleware(req: Request, next: Next) -> Response {
+    let configured = req
+        .extensions()
+        .get::<Arc<Config>>()
+        .map(|config| config.server.error_format)
+        .unwrap_or_default();
+    let wants_problem = configured == ErrorFormat::Problem || accepts_problem_json(req.headers());
+    let request_id = req.extensions().get::<String>().cloned();
+
+    let mut response = next.run(req).await;
+
+    // Taking the report leaves nothing to do for this middleware further out
+    let Some(report) = response.extensions_mut().remove::<ErrorReport>() else {
+        return response;
+    };
+    if !wants_problem {
+        return response;
+    }
+
+    let body = match serde_json::to_vec(&report.into_problem(request_id)) {
+        Ok(body) => body,
+        Err(_) => return response,
+    };
+
+    let (mut parts, _) = response.into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts
+        .headers
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// Whether the `Accept` header lists `application/problem+json` as acceptable
+///
+/// A media range with `q=0` marks the type as not acceptable; a malformed
+/// q-value counts as the default of 1.
+fn accepts_problem_json(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|range| {
+            let mut parts = range.split(';');
+            let media_type = parts.next().unwrap_or_default().trim();
+            let quality = parts
+                .filter_map(|param| param.split_once('='))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+                .and_then(|(_, value)| value.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            media_type.eq_ignore_ascii_case(PROBLEM_JSON) && quality > 0.0
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accepts(values: &[&str]) -> bool {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(header::ACCEPT, HeaderValue::from_str(value).unwrap());
+        }
+        accepts_problem_json(&headers)
+    }
+
+    #[test]
+    fn problem_json_must_be_listed() {
+        assert!(!accepts(&[]));
+        assert!(!accepts(&["application/json", "*/*"]));
+        assert!(accepts(&["application/json, Application/Problem+JSON"]));
+        assert!(accepts(&["text/html", "application/problem+json"]));
+    }
+
+    #[test]
+    fn zero_quality_means_not_acceptable() {
+        assert!(!accepts(&["application/problem+json;q=0"]));
+        assert!(!accepts(&[
+            "application/problem+json; charset=utf-8; Q=0.000"
+        ]));
+        assert!(accepts(&[
+            "application/problem+json;q=0.1, application/json"
+        ]));
+        assert!(accepts(&["application/problem+json; q=1"]));
+        assert!(accepts(&["application/problem+json; q=bad"]));
+    }
+}
@@ -0,0 +1,23 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    services::maintenance::MaintenanceMode,
+};
+
+/// Turn requests away with `503` while maintenance mode is on
+pub async fn maintenance_middleware(
+    State(maintenance): State<Arc<MaintenanceMode>>,
+    req: Request,
+    next: Next,
+) -> ApiResult<Response> {
+    match maintenance.active_message() {
+        Some(message) => Err(ApiError::Maintenance(message)),
+        None => Ok(next.run(req).await),
+    }
+}
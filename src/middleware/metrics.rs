@@ -0,0 +1,33 @@
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+
+use crate::services::metrics::Metrics;
+
+/// Record request counts and latency per route
+///
+/// Requests are labelled with the matched route template rather than the raw
+/// path, so unmatched paths (such as static files) share a single label.
+pub async fn metrics_middleware(req: Request, next: Next) -> Response {
+    let start_time = Instant::now();
+    let method = req.method().to_string();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let response = next.run(req).await;
+
+    Metrics::global().record_request(
+        &route,
+        &method,
+        response.status().as_u16(),
+        start_time.elapsed(),
+    );
+
+    response
+}
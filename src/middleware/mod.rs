@@ -1,3 +1,15 @@
+pub mod admin;
+pub mod api_key;
 pub mod common;
+pub mod error_format;
+pub mod maintenance;
+pub mod metrics;
+pub mod rate_limit;
 
+pub use admin::admin_auth_middleware;
+pub use api_key::{api_key_middleware, ApiKeyStore, AuthenticatedKey};
 pub use common::{create_cors_layer, request_id_middleware};
+pub use error_format::error_format_middleware;
+pub use maintenance::maintenance_middleware;
+pub use metrics::metrics_middleware;
+pub use rate_limit::{rate_limit_middleware, IpRateLimiter};
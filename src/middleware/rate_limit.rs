@@ -0,0 +1,303 @@
+use anyhow::Context;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use governor::{
+    clock::{Clock, DefaultClock},
+    middleware::StateInformationMiddleware,
+    DefaultKeyedRateLimiter, Quota,
+};
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+use std::{
+    net::{IpAddr, SocketAddr},
+    num::NonZeroU32,
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::{error::ApiError, util::ceil_secs};
+
+pub const X_RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+pub const X_RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
+pub const X_RATELIMIT_RESET: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+
+/// Per-IP rate limiting settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    /// Sustained number of requests per second allowed for each client IP
+    pub requests_per_second: u32,
+    /// Number of requests a client may send at once before being throttled
+    pub burst_size: u32,
+    /// Proxies whose `X-Forwarded-For` header is trusted to carry the client IP
+    pub trusted_proxies: Vec<IpNet>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_second: 10,
+            burst_size: 10,
+            trusted_proxies: vec!["127.0.0.1/32".parse().unwrap(), "::1/128".parse().unwrap()],
+        }
+    }
+}
+
+/// Parse a trusted proxy given as a single IP address or a CIDR range
+pub fn parse_trusted_proxy(value: &str) -> Result<IpNet, String> {
+    let value = value.trim();
+    value
+        .parse::<IpNet>()
+        .or_else(|_| value.parse::<IpAddr>().map(IpNet::from))
+        .map_err(|_| format!("Invalid trusted proxy '{value}'"))
+}
+
+/// Rate limit state for a client after a request was allowed
+#[derive(Debug)]
+pub struct RateLimitStatus {
+    /// Requests the client can still send right now
+    pub remaining: u32,
+    /// Time until the client's full burst capacity is restored
+    pub reset_after: Duration,
+}
+
+/// Keyed rate limiter tracking a request budget per client IP
+pub struct IpRateLimiter {
+    limiter: DefaultKeyedRateLimiter<IpAddr, StateInformationMiddleware>,
+    quota: Quota,
+    requests_per_second: u32,
+    trusted_proxies: Vec<IpNet>,
+}
+
+impl IpRateLimiter {
+    /// Create a rate limiter from the given settings
+    pub fn new(config: &RateLimitConfig) -> anyhow::Result<Self> {
+        let per_second = NonZeroU32::new(config.requests_per_second)
+            .context("Rate limit requests per second must be greater than zero")?;
+        let burst = NonZeroU32::new(config.burst_size)
+            .context("Rate limit burst size must be greater than zero")?;
+
+        let quota = Quota::per_second(per_second).allow_burst(burst);
+
+        Ok(Self {
+            limiter: DefaultKeyedRateLimiter::keyed(quota)
+                .with_middleware::<StateInformationMiddleware>(),
+            quota,
+            requests_per_second: per_second.get(),
+            trusted_proxies: config.trusted_proxies.clone(),
+        })
+    }
+
+    /// Maximum number of requests a client can send in one burst
+    pub fn limit(&self) -> u32 {
+        self.quota.burst_size().get()
+    }
+
+    /// Resolve the client IP, honoring `X-Forwarded-For` only from trusted proxies
+    ///
+    /// The header is walked from right to left, skipping trusted hops, so a client
+    /// cannot spoof its address by prepending entries.
+    pub fn client_ip(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        if !self.is_trusted(peer) {
+            return peer;
+        }
+
+        let hops: Vec<&str> = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .collect();
+
+        let mut client = peer;
+        for hop in hops.into_iter().rev() {
+            match hop.parse::<IpAddr>() {
+                Ok(ip) => {
+                    client = ip;
+                    if !self.is_trusted(ip) {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+
+        client
+    }
+
+    /// Record a request for the client, returning how long to wait if it is over quota
+    pub fn check(&self, client: IpAddr) -> Result<RateLimitStatus, Duration> {
+        match self.limiter.check_key(&client) {
+            Ok(snapshot) => {
+                let remaining = snapshot.remaining_burst_capacity();
+                let used = self.limit() - remaining;
+                Ok(RateLimitStatus {
+                    remaining,
+                    reset_after: self.quota.replenish_interval() * used,
+                })
+            }
+            Err(not_until) => Err(not_until.wait_time_from(DefaultClock::default().now())),
+        }
+    }
+
+    /// Drop state for clients whose budget has been fully replenished
+    pub fn retain_recent(&self) {
+        self.limiter.retain_recent();
+        self.limiter.shrink_to_fit();
+    }
+
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|net| net.contains(&ip))
+    }
+}
+
+/// Enforce the per-IP rate limit and report the client's budget in response headers
+pub async fn rate_limit_middleware(
+    State(limiter): State<Arc<IpRateLimiter>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(peer) = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+    else {
+        return next.run(req).await;
+    };
+
+    let client = limiter.client_ip(peer, req.headers());
+
+    match limiter.check(client) {
+        Ok(status) => {
+            let mut response = next.run(req).await;
+            insert_headers(
+                response.headers_mut(),
+                limiter.limit(),
+                status.remaining,
+                status.reset_after,
+            );
+            response
+        }
+        Err(retry_after) => {
+            let mut response = ApiError::RateLimitExceeded {
+                limit: limiter.requests_per_second,
+                retry_after,
+            }
+            .into_response();
+            insert_headers(response.headers_mut(), limiter.limit(), 0, retry_after);
+            response
+        }
+    }
+}
+
+fn insert_headers(headers: &mut HeaderMap, limit: u32, remaining: u32, reset_after: Duration) {
+    headers.insert(X_RATELIMIT_LIMIT, HeaderValue::from(limit));
+    headers.insert(X_RATELIMIT_REMAINING, HeaderValue::from(remaining));
+    headers.insert(X_RATELIMIT_RESET, HeaderValue::from(ceil_secs(reset_after)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(requests_per_second: u32, burst_size: u32, trusted: &[&str]) -> IpRateLimiter {
+        IpRateLimiter::new(&RateLimitConfig {
+            requests_per_second,
+            burst_size,
+            trusted_proxies: trusted
+                .iter()
+                .map(|proxy| parse_trusted_proxy(proxy).unwrap())
+                .collect(),
+        })
+        .unwrap()
+    }
+
+    fn forwarded_for(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", value.parse().unwrap());
+        headers
+    }
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn trusted_proxies_accept_addresses_and_ranges() {
+        assert_eq!(
+            parse_trusted_proxy(" 10.0.0.1 ").unwrap(),
+            "10.0.0.1/32".parse::<IpNet>().unwrap()
+        );
+        assert_eq!(
+            parse_trusted_proxy("10.0.0.0/8").unwrap(),
+            "10.0.0.0/8".parse::<IpNet>().unwrap()
+        );
+        assert!(parse_trusted_proxy("proxy.local").is_err());
+    }
+
+    #[test]
+    fn zero_limits_are_rejected() {
+        let config = |requests_per_second, burst_size| RateLimitConfig {
+            requests_per_second,
+            burst_size,
+            ..RateLimitConfig::default()
+        };
+        assert!(IpRateLimiter::new(&config(0, 10)).is_err());
+        assert!(IpRateLimiter::new(&config(10, 0)).is_err());
+    }
+
+    #[test]
+    fn forwarded_for_is_ignored_from_untrusted_peers() {
+        let limiter = limiter(10, 10, &["10.0.0.1"]);
+        let client = limiter.client_ip(ip("203.0.113.9"), &forwarded_for("198.51.100.1"));
+        assert_eq!(client, ip("203.0.113.9"));
+    }
+
+    #[test]
+    fn forwarded_for_is_walked_from_the_right_past_trusted_hops() {
+        let limiter = limiter(10, 10, &["10.0.0.0/8"]);
+
+        // A spoofed entry prepended by the client is never reached
+        let headers = forwarded_for("192.0.2.66, 198.51.100.1, 10.0.0.2");
+        assert_eq!(
+            limiter.client_ip(ip("10.0.0.1"), &headers),
+            ip("198.51.100.1")
+        );
+
+        // Only trusted hops: the leftmost one is the client
+        let headers = forwarded_for("10.0.0.3, 10.0.0.2");
+        assert_eq!(limiter.client_ip(ip("10.0.0.1"), &headers), ip("10.0.0.3"));
+
+        // A malformed hop stops the walk at the last address seen
+        let headers = forwarded_for("198.51.100.1, garbage");
+        assert_eq!(limiter.client_ip(ip("10.0.0.1"), &headers), ip("10.0.0.1"));
+
+        // No header at all
+        assert_eq!(
+            limiter.client_ip(ip("10.0.0.1"), &HeaderMap::new()),
+            ip("10.0.0.1")
+        );
+    }
+
+    #[test]
+    fn burst_is_allowed_then_clients_wait() {
+        let limiter = limiter(1, 2, &[]);
+        let client = ip("203.0.113.9");
+
+        let first = limiter.check(client).unwrap();
+        assert_eq!(first.remaining, 1);
+        assert_eq!(first.reset_after, Duration::from_secs(1));
+        assert_eq!(limiter.check(client).unwrap().remaining, 0);
+
+        let wait = limiter.check(client).unwrap_err();
+        assert!(wait > Duration::ZERO && wait <= Duration::from_secs(1));
+
+        // Budgets are kept per client
+        assert_eq!(limiter.check(ip("203.0.113.10")).unwrap().remaining, 1);
+    }
+}
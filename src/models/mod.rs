@@ -1,5 +0,0 @@
-pub mod requests;
-pub mod responses;
-
-pub use requests::*;
-pub use responses::*;
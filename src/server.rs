@@ -0,0 +1,172 @@
+use anyhow::Context;
+use axum::{
+    extract::DefaultBodyLimit,
+    middleware::{from_fn, from_fn_with_state},
+    Extension, Router,
+};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+use tower::ServiceBuilder;
+use tower_http::{limit::RequestBodyLimitLayer, services::ServeDir, trace::TraceLayer};
+use tracing::{error, info};
+use utoipa::OpenApi;
+
+use crate::{
+    api::{self, handlers::ApiDoc, AppState},
+    config::Config,
+    middleware::{self, ApiKeyStore, IpRateLimiter},
+    services::{
+        jobs::JobManager, maintenance::MaintenanceMode, reload::ModelReloader, LibPostalService,
+    },
+};
+
+/// Run the standalone server until it fails
+///
+/// Serves the address endpoints under `/api/v1` behind rate limiting, API keys
+/// and maintenance mode, along with the probes, metrics, documentation, admin
+/// endpoints and landing page. [`LibPostalService::initialize`] must have been
+/// called first.
+pub async fn serve(config: Arc<Config>) -> anyhow::Result<()> {
+    // Resume unfinished bulk jobs and start the job runners
+    JobManager::initialize(&config.jobs, &config.validation).await?;
+
+    // Run the LibPostal self-test periodically for the readiness probe
+    let self_test_interval = Duration::from_secs(config.health.self_test_interval_secs);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(self_test_interval);
+        loop {
+            interval.tick().await;
+            let Ok(service) = LibPostalService::global() else {
+                continue;
+            };
+            let result = service.run_self_test().await;
+            if !result.passed {
+                error!(error = ?result.error, "LibPostal self-test failed");
+            }
+        }
+    });
+
+    // Set up per-IP rate limiting
+    let rate_limiter = Arc::new(IpRateLimiter::new(&config.rate_limit)?);
+    info!(
+        requests_per_second = config.rate_limit.requests_per_second,
+        burst_size = config.rate_limit.burst_size,
+        "Rate limiting enabled"
+    );
+
+    // Periodically forget clients whose budget has been fully replenished
+    let housekeeping_limiter = rate_limiter.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            housekeeping_limiter.retain_recent();
+        }
+    });
+
+    // API keys are only required when a keys file is configured
+    let api_keys = match config.auth.keys_file.as_deref() {
+        Some(path) => {
+            let store = Arc::new(ApiKeyStore::load(path)?);
+            info!(keys = store.len(), "API key authentication enabled");
+            Some(store)
+        }
+        None => {
+            info!("API key authentication disabled; set an API keys file to enable it");
+            None
+        }
+    };
+
+    let state = AppState::libpostal(config.clone());
+    let mut api_routes = crate::router(state.clone()).merge(
+        api::create_job_routes()
+            .layer(RequestBodyLimitLayer::new(config.jobs.max_upload_bytes))
+            .with_state(state.clone()),
+    );
+    if let Some(ref store) = api_keys {
+        api_routes = api_routes.layer(from_fn_with_state(
+            store.clone(),
+            middleware::api_key_middleware,
+        ));
+    }
+
+    // Maintenance mode turns API requests away before they use up any key quota
+    let maintenance = Arc::new(MaintenanceMode::new());
+    api_routes = api_routes.layer(from_fn_with_state(
+        maintenance.clone(),
+        middleware::maintenance_middleware,
+    ));
+
+    // Build the application
+    let mut app = Router::new()
+        // API routes, rate limited per IP before any key is checked
+        .nest(
+            "/api/v1",
+            api_routes.layer(from_fn_with_state(
+                rate_limiter,
+                middleware::rate_limit_middleware,
+            )),
+        )
+        // Probes and Prometheus metrics
+        .merge(api::create_monitoring_routes().with_state(state.clone()))
+        // Documentation routes - merge SwaggerUi directly
+        .merge(
+            utoipa_swagger_ui::SwaggerUi::new("/docs")
+                .url("/api-docs/openapi.json", ApiDoc::openapi()),
+        )
+        // Static file serving for the landing page
+        .fallback_service(ServeDir::new("static"));
+
+    // Admin endpoints are only served when a token is configured
+    match config.admin.token.as_deref() {
+        Some(token) => {
+            let mut admin_routes = api::create_admin_routes()
+                .layer(Extension(maintenance))
+                .layer(Extension(Arc::new(ModelReloader::new())))
+                .with_state(state);
+            if let Some(store) = api_keys {
+                admin_routes = admin_routes.layer(Extension(store));
+            }
+
+            app = app.merge(
+                admin_routes
+                    .layer(RequestBodyLimitLayer::new(config.server.body_limit_bytes))
+                    .layer(from_fn_with_state(
+                        Arc::<str>::from(token),
+                        middleware::admin_auth_middleware,
+                    )),
+            );
+            info!("Admin endpoints enabled");
+        }
+        None => info!("Admin endpoints disabled; set an admin token to enable them"),
+    }
+
+    let app = app
+        // Record metrics after routing so the matched route is known
+        .layer(from_fn(middleware::metrics_middleware))
+        .layer(
+            ServiceBuilder::new()
+                .layer(TraceLayer::new_for_http())
+                .layer(middleware::create_cors_layer(&config.server.cors_origins))
+                .layer(from_fn(middleware::request_id_middleware))
+                // Body size limits are applied per route group above
+                .layer(DefaultBodyLimit::disable())
+                .layer(Extension(config.clone()))
+                .layer(from_fn(middleware::error_format_middleware)),
+        );
+
+    // Start the server
+    let addr = config.server.bind_address;
+    info!("LibPostal API server listening on {}", addr);
+    info!("API documentation available at: http://{}/docs", addr);
+    info!("Landing page available at: http://{}", addr);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .context("Unable to serve application")?;
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use lru::LruCache;
+use std::{
+    hash::Hash,
+    num::NonZeroUsize,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::{config::CacheConfig, models::AppliedHints, services::metrics::Metrics};
+
+/// Value returned by a cached lookup, noting whether it came from the cache
+pub struct Cached<T> {
+    pub value: T,
+    pub hit: bool,
+}
+
+/// Key for cached parse results: the normalized address and resolved hints
+pub type ParseKey = (String, AppliedHints);
+
+/// Key for cached normalize results: the normalized address, level and languages
+pub type NormalizeKey = (String, Option<String>, Option<Vec<String>>);
+
+/// In-process result caches for parse and normalize
+pub struct ResultCache {
+    pub parse: TtlCache<ParseKey, libpostal_rs::ParsedAddress>,
+    pub normalize: TtlCache<NormalizeKey, libpostal_rs::NormalizedAddress>,
+}
+
+impl ResultCache {
+    /// Create the caches from the cache settings; a capacity of 0 disables them
+    pub fn new(config: &CacheConfig) -> Self {
+        let ttl = Duration::from_secs(config.ttl_secs);
+
+        Self {
+            parse: TtlCache::new("parse", config.capacity, ttl),
+            normalize: TtlCache::new("normalize", config.capacity, ttl),
+        }
+    }
+
+    /// Whether results are being cached at all
+    pub fn is_enabled(&self) -> bool {
+        self.parse.is_enabled()
+    }
+
+    /// Drop every cached result, returning how many were removed
+    pub fn clear(&self) -> usize {
+        self.parse.clear() + self.normalize.clear()
+    }
+
+    /// Cache key form of an address: whitespace collapsed and lowercased
+    pub fn address_key(address: &str) -> String {
+        address
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    }
+}
+
+/// Bounded LRU cache whose entries expire a fixed time after insertion
+pub struct TtlCache<K: Hash + Eq, V> {
+    operation: &'static str,
+    entries: Option<Mutex<LruCache<K, (Instant, V)>>>,
+    ttl: Duration,
+}
+
+impl<K: Hash + Eq, V: Clone> TtlCache<K, V> {
+    fn new(operation: &'static str, capacity: usize, ttl: Duration) -> Self {
+        Self {
+            operation,
+            entries: NonZeroUsize::new(capacity).map(|cap| Mutex::new(LruCache::new(cap))),
+            ttl,
+        }
+    }
+
+    /// Whether this cache stores anything
+    pub fn is_enabled(&self) -> bool {
+        self.entries.is_some()
+    }
+
+    /// Look up a live entry, dropping it if it has expired
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut entries = self.entries.as_ref()?.lock().ok()?;
+
+        let value = match entries.get(key) {
+            Some((inserted, value)) if inserted.elapsed() < self.ttl => Some(value.clone()),
+            Some(_) => {
+                entries.pop(key);
+                None
+            }
+            None => None,
+        };
+
+        Metrics::global().record_cache_lookup(self.operation, value.is_some());
+        Metrics::global().set_cache_entries(self.operation, entries.len());
+        value
+    }
+
+    /// Store a value, evicting the least recently used entry when full
+    pub fn insert(&self, key: K, value: V) {
+        let Some(Ok(mut entries)) = self.entries.as_ref().map(Mutex::lock) else {
+            return;
+        };
+
+        entries.put(key, (Instant::now(), value));
+        Metrics::global().set_cache_entries(self.operation, entries.len());
+    }
+
+    /// Drop every entry, returning how many were removed
+    pub fn clear(&self) -> usize {
+        let Some(Ok(mut entries)) = self.entries.as_ref().map(Mutex::lock) else {
+            return 0;
+        };
+
+        let removed = entries.len();
+        entries.clear();
+        Metrics::global().set_cache_entries(self.operation, 0);
+        removed
+    }
+}
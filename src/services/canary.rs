@@ -0,0 +1,202 @@
+use libpostal_rs::ParsedAddress;
+
+use crate::models::{CanaryCheck, CanaryReport};
+
+/// A known address and components a working model must find in it
+struct Canary {
+    address: &'static str,
+    expected: &'static [(&'static str, &'static str)],
+}
+
+/// Addresses a model must parse correctly before it is put in service
+///
+/// They cover the formats most of our traffic uses. Expected values are
+/// compared case-insensitively, as libpostal lowercases its output.
+const CANARIES: &[Canary] = &[
+    Canary {
+        address: "781 Franklin Ave Crown Heights Brooklyn NYC NY 11216 USA",
+        expected: &[
+            ("house_number", "781"),
+            ("road", "franklin ave"),
+            ("postcode", "11216"),
+        ],
+    },
+    Canary {
+        address: "The Book Club 100-106 Leonard St Shoreditch London EC2A 4RH, United Kingdom",
+        expected: &[
+            ("house_number", "100-106"),
+            ("road", "leonard st"),
+            ("postcode", "ec2a 4rh"),
+        ],
+    },
+    Canary {
+        address: "Platz der Republik 1, 11011 Berlin",
+        expected: &[
+            ("road", "platz der republik"),
+            ("house_number", "1"),
+            ("postcode", "11011"),
+            ("city", "berlin"),
+        ],
+    },
+];
+
+/// Parse every canary address and compare the components with what is expected
+///
+/// `parse` is normally libpostal's parser, which blocks, so call this from a
+/// blocking thread.
+pub fn run_canaries(parse: impl Fn(&str) -> libpostal_rs::Result<ParsedAddress>) -> CanaryReport {
+    let checks: Vec<CanaryCheck> = CANARIES
+        .iter()
+        .map(|canary| match parse(canary.address) {
+            Ok(parsed) => {
+                let problems: Vec<String> = canary
+                    .expected
+                    .iter()
+                    .filter_map(|&(label, expected)| match component(&parsed, label) {
+                        Some(actual) if actual.trim().eq_ignore_ascii_case(expected) => None,
+                        Some(actual) => Some(format!(
+                            "{label}: expected \"{expected}\", got \"{actual}\""
+                        )),
+                        None => Some(format!("{label}: expected \"{expected}\", got none")),
+                    })
+                    .collect();
+
+                CanaryCheck {
+                    address: canary.address.to_string(),
+                    passed: problems.is_empty(),
+                    problems,
+                    error: None,
+                }
+            }
+            Err(e) => CanaryCheck {
+                address: canary.address.to_string(),
+                passed: false,
+                problems: Vec::new(),
+                error: Some(e.to_string()),
+            },
+        })
+        .collect();
+
+    CanaryReport {
+        passed: checks.iter().all(|check| check.passed),
+        checks,
+    }
+}
+
+/// One-line description of the canaries that failed, for errors and logs
+pub fn failure_summary(report: &CanaryReport) -> String {
+    report
+        .checks
+        .iter()
+        .filter(|check| !check.passed)
+        .map(|check| {
+            let detail = match &check.error {
+                Some(error) => error.clone(),
+                None => check.problems.join(", "),
+            };
+            format!("'{}' ({detail})", check.address)
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn component<'a>(parsed: &'a ParsedAddress, label: &str) -> Option<&'a str> {
+    let value = match label {
+        "house_number" => &parsed.house_number,
+        "road" => &parsed.road,
+        "postcode" => &parsed.postcode,
+        "city" => &parsed.city,
+        _ => return None,
+    };
+    value.as_deref()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse the way a working model does, from the expected components
+    fn expected(address: &str) -> libpostal_rs::Result<ParsedAddress> {
+        let canary = CANARIES
+            .iter()
+            .find(|canary| canary.address == address)
+            .expect("only canary addresses are parsed");
+
+        let mut parsed = ParsedAddress::default();
+        for &(label, value) in canary.expected {
+            let value = Some(value.to_string());
+            match label {
+                "house_number" => parsed.house_number = value,
+                "road" => parsed.road = value,
+                "postcode" => parsed.postcode = value,
+                "city" => parsed.city = value,
+                _ => unreachable!("unknown canary label {label}"),
+            }
+        }
+        Ok(parsed)
+    }
+
+    #[test]
+    fn a_working_model_passes_every_canary() {
+        let report = run_canaries(expected);
+
+        assert!(report.passed);
+        assert_eq!(report.checks.len(), CANARIES.len());
+        assert!(report.checks.iter().all(|check| check.problems.is_empty()));
+        assert_eq!(failure_summary(&report), "");
+    }
+
+    #[test]
+    fn components_are_compared_ignoring_case_and_padding() {
+        let report = run_canaries(|address| {
+            let mut parsed = expected(address)?;
+            parsed.road = parsed.road.map(|road| format!(" {} ", road.to_uppercase()));
+            Ok(parsed)
+        });
+
+        assert!(report.passed);
+    }
+
+    #[test]
+    fn wrong_and_missing_components_are_listed() {
+        let report = run_canaries(|address| {
+            let mut parsed = expected(address)?;
+            if address.contains("Berlin") {
+                parsed.city = None;
+                parsed.postcode = Some("10117".to_string());
+            }
+            Ok(parsed)
+        });
+
+        assert!(!report.passed);
+        let failed: Vec<_> = report.checks.iter().filter(|check| !check.passed).collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(
+            failed[0].problems,
+            [
+                "postcode: expected \"11011\", got \"10117\"",
+                "city: expected \"berlin\", got none",
+            ]
+        );
+        assert_eq!(
+            failure_summary(&report),
+            "'Platz der Republik 1, 11011 Berlin' (postcode: expected \"11011\", got \"10117\", city: expected \"berlin\", got none)"
+        );
+    }
+
+    #[test]
+    fn parse_errors_fail_the_canary() {
+        let report = run_canaries(|_| Err(libpostal_rs::Error::parse_error("model not loaded")));
+
+        assert!(!report.passed);
+        assert!(report
+            .checks
+            .iter()
+            .all(|check| !check.passed && check.problems.is_empty() && check.error.is_some()));
+
+        let summary = failure_summary(&report);
+        assert_eq!(summary.matches("; ").count(), CANARIES.len() - 1);
+        assert!(summary.starts_with(&format!("'{}' (", CANARIES[0].address)));
+        assert!(summary.contains("model not loaded"));
+    }
+}
@@ -0,0 +1,122 @@
+use futures_util::future::BoxFuture;
+use libpostal_rs::{NormalizedAddress, ParsedAddress};
+
+use crate::{
+    error::ApiResult,
+    models::{ModelStatus, QueueMetrics, SelfTestResult},
+    services::{cache::Cached, LibPostalService},
+};
+
+/// Address parsing and normalization, as used by the request handlers
+///
+/// [`LibPostalService`] is the production engine. Handlers get theirs from the
+/// router state, so tests can serve the API from a fake engine instead of a
+/// loaded libpostal model.
+pub trait AddressEngine: Send + Sync {
+    /// Parse an address into components
+    fn parse_address<'a>(
+        &'a self,
+        address: &'a str,
+        language: Option<&'a str>,
+        country: Option<&'a str>,
+    ) -> BoxFuture<'a, ApiResult<Cached<ParsedAddress>>>;
+
+    /// Expand an address into its normalized forms
+    fn normalize_address<'a>(
+        &'a self,
+        address: &'a str,
+        level: Option<&'a str>,
+        languages: Option<&'a [String]>,
+    ) -> BoxFuture<'a, ApiResult<Cached<NormalizedAddress>>>;
+
+    /// Check that the engine can serve requests
+    fn health_check(&self) -> BoxFuture<'_, ApiResult<()>>;
+
+    /// Whether results may come from a cache, so responses report cache usage
+    fn cache_enabled(&self) -> bool;
+
+    /// Drop every cached result, returning how many entries were removed
+    fn flush_cache(&self) -> usize;
+
+    /// Outcome of the last background self-test, if one has run
+    fn last_self_test(&self) -> Option<SelfTestResult>;
+
+    /// The model serving requests and the data it was loaded from
+    fn model_status(&self) -> ModelStatus;
+
+    /// Load on the workers running the engine
+    fn queue_metrics(&self) -> QueueMetrics;
+
+    /// Calls one batch may keep in flight at once
+    ///
+    /// Enough to keep every worker busy, but no more than the workers and the
+    /// wait queue can take, so a batch alone never gets `QUEUE_FULL`.
+    fn batch_concurrency(&self) -> usize {
+        let metrics = self.queue_metrics();
+        (metrics.workers + metrics.queue_depth)
+            .min(metrics.workers * 2)
+            .max(1)
+    }
+}
+
+impl AddressEngine for LibPostalService {
+    fn parse_address<'a>(
+        &'a self,
+        address: &'a str,
+        language: Option<&'a str>,
+        country: Option<&'a str>,
+    ) -> BoxFuture<'a, ApiResult<Cached<ParsedAddress>>> {
+        Box::pin(LibPostalService::parse_address(
+            self, address, language, country,
+        ))
+    }
+
+    fn normalize_address<'a>(
+        &'a self,
+        address: &'a str,
+        level: Option<&'a str>,
+        languages: Option<&'a [String]>,
+    ) -> BoxFuture<'a, ApiResult<Cached<NormalizedAddress>>> {
+        Box::pin(LibPostalService::normalize_address(
+            self, address, level, languages,
+        ))
+    }
+
+    fn health_check(&self) -> BoxFuture<'_, ApiResult<()>> {
+        Box::pin(LibPostalService::health_check(self))
+    }
+
+    fn cache_enabled(&self) -> bool {
+        LibPostalService::cache_enabled(self)
+    }
+
+    fn flush_cache(&self) -> usize {
+        LibPostalService::flush_cache(self)
+    }
+
+    fn last_self_test(&self) -> Option<SelfTestResult> {
+        LibPostalService::last_self_test(self)
+    }
+
+    fn model_status(&self) -> ModelStatus {
+        LibPostalService::model_status(self)
+    }
+
+    fn queue_metrics(&self) -> QueueMetrics {
+        LibPostalService::queue_metrics(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::fake::FakeEngine;
+
+    #[test]
+    fn batch_concurrency_fits_in_the_workers_and_queue() {
+        assert_eq!(FakeEngine::with_queue(4, 1024).batch_concurrency(), 8);
+        assert_eq!(FakeEngine::with_queue(4, 2).batch_concurrency(), 6);
+        assert_eq!(FakeEngine::with_queue(4, 0).batch_concurrency(), 4);
+        assert_eq!(FakeEngine::with_queue(0, 0).batch_concurrency(), 1);
+    }
+}
@@ -0,0 +1,239 @@
+use std::sync::Arc;
+
+use futures_util::future::BoxFuture;
+use libpostal_rs::{NormalizedAddress, ParsedAddress};
+
+use crate::{
+    error::{ApiError, ApiResult},
+    models::{DataIntegrityStatus, DataVersion, ModelStatus, QueueMetrics, SelfTestResult},
+    services::{cache::Cached, engine::AddressEngine},
+};
+
+/// Street suffixes the fake expands when normalizing
+const ABBREVIATIONS: &[(&str, &str)] = &[("st", "street"), ("ave", "avenue"), ("rd", "road")];
+
+/// Deterministic stand-in for libpostal, for testing the API without data files
+///
+/// Addresses are read as `<house number> <road>, <city>, <postcode or state>,
+/// <country>`, lowercased like libpostal output; anything after that goes to
+/// `other`. Normalizing yields the lowercased address, plus a second expansion
+/// with common street suffixes spelled out when it has any.
+#[derive(Clone)]
+pub struct FakeEngine {
+    failure: Option<Arc<dyn Fn() -> ApiError + Send + Sync>>,
+    unhealthy: bool,
+    corrupt_data: bool,
+    workers: usize,
+    queue_depth: usize,
+}
+
+impl Default for FakeEngine {
+    fn default() -> Self {
+        Self {
+            failure: None,
+            unhealthy: false,
+            corrupt_data: false,
+            workers: 1,
+            queue_depth: 0,
+        }
+    }
+}
+
+impl FakeEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report `workers` workers and a wait queue of `queue_depth` in the queue metrics
+    pub fn with_queue(workers: usize, queue_depth: usize) -> Self {
+        Self {
+            workers,
+            queue_depth,
+            ..Self::default()
+        }
+    }
+
+    /// Fail every parse and normalize call with the error `failure` returns
+    pub fn failing(failure: impl Fn() -> ApiError + Send + Sync + 'static) -> Self {
+        Self {
+            failure: Some(Arc::new(failure)),
+            ..Self::default()
+        }
+    }
+
+    /// Fail health checks and self-tests
+    pub fn unhealthy() -> Self {
+        Self {
+            unhealthy: true,
+            ..Self::default()
+        }
+    }
+
+    /// Report that the data failed its integrity check, while still answering
+    pub fn corrupt_data() -> Self {
+        Self {
+            corrupt_data: true,
+            ..Self::default()
+        }
+    }
+
+    fn check(&self) -> ApiResult<()> {
+        match &self.failure {
+            Some(failure) => Err(failure()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl AddressEngine for FakeEngine {
+    fn parse_address<'a>(
+        &'a self,
+        address: &'a str,
+        _language: Option<&'a str>,
+        _country: Option<&'a str>,
+    ) -> BoxFuture<'a, ApiResult<Cached<ParsedAddress>>> {
+        Box::pin(async move {
+            self.check()?;
+            Ok(Cached {
+                value: parse(address),
+                hit: false,
+            })
+        })
+    }
+
+    fn normalize_address<'a>(
+        &'a self,
+        address: &'a str,
+        _level: Option<&'a str>,
+        _languages: Option<&'a [String]>,
+    ) -> BoxFuture<'a, ApiResult<Cached<NormalizedAddress>>> {
+        Box::pin(async move {
+            self.check()?;
+            Ok(Cached {
+                value: normalize(address),
+                hit: false,
+            })
+        })
+    }
+
+    fn health_check(&self) -> BoxFuture<'_, ApiResult<()>> {
+        Box::pin(async move {
+            if self.unhealthy {
+                return Err(ApiError::ServiceUnavailable(
+                    "LibPostal health check failed".to_string(),
+                ));
+            }
+            Ok(())
+        })
+    }
+
+    fn cache_enabled(&self) -> bool {
+        false
+    }
+
+    fn flush_cache(&self) -> usize {
+        0
+    }
+
+    fn last_self_test(&self) -> Option<SelfTestResult> {
+        Some(SelfTestResult {
+            passed: !self.unhealthy,
+            checked_at: chrono::DateTime::UNIX_EPOCH.to_rfc3339(),
+            duration_ms: 0,
+            error: self
+                .unhealthy
+                .then(|| "LibPostal health check failed".to_string()),
+        })
+    }
+
+    fn model_status(&self) -> ModelStatus {
+        ModelStatus {
+            data_dir: "fake".to_string(),
+            data_version: DataVersion::default(),
+            loaded_at: chrono::DateTime::UNIX_EPOCH.to_rfc3339(),
+            load_time_ms: 0,
+            data_integrity: if self.corrupt_data {
+                DataIntegrityStatus {
+                    status: "failed".to_string(),
+                    message: Some("Checksum mismatch".to_string()),
+                }
+            } else {
+                DataIntegrityStatus {
+                    status: "skipped".to_string(),
+                    message: None,
+                }
+            },
+        }
+    }
+
+    fn queue_metrics(&self) -> QueueMetrics {
+        QueueMetrics {
+            workers: self.workers,
+            active: 0,
+            queued: 0,
+            queue_depth: self.queue_depth,
+            completed: 0,
+            rejected: 0,
+            average_wait_ms: 0.0,
+        }
+    }
+}
+
+fn parse(address: &str) -> ParsedAddress {
+    let lowered = address.to_lowercase();
+    let mut parts = lowered
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty());
+    let mut parsed = ParsedAddress::default();
+
+    if let Some(street) = parts.next() {
+        match street.split_once(' ') {
+            Some((number, road)) if number.starts_with(|c: char| c.is_ascii_digit()) => {
+                parsed.house_number = Some(number.to_string());
+                parsed.road = Some(road.trim().to_string());
+            }
+            _ => parsed.road = Some(street.to_string()),
+        }
+    }
+    parsed.city = parts.next().map(str::to_string);
+    if let Some(part) = parts.next() {
+        if part.contains(|c: char| c.is_ascii_digit()) {
+            parsed.postcode = Some(part.to_string());
+        } else {
+            parsed.state = Some(part.to_string());
+        }
+    }
+    parsed.country = parts.next().map(str::to_string);
+    parsed.other = parts.map(str::to_string).collect();
+
+    parsed
+}
+
+fn normalize(address: &str) -> NormalizedAddress {
+    let words: Vec<String> = address
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    let mut expansions = vec![words.join(" ")];
+    let expanded: Vec<&str> = words
+        .iter()
+        .map(|word| {
+            ABBREVIATIONS
+                .iter()
+                .find(|(short, _)| short == word)
+                .map_or(word.as_str(), |(_, long)| long)
+        })
+        .collect();
+    let expanded = expanded.join(" ");
+    if expanded != expansions[0] {
+        expansions.push(expanded);
+    }
+
+    NormalizedAddress {
+        original: address.to_string(),
+        expansions,
+    }
+}
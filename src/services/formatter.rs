@@ -0,0 +1,220 @@
+use serde::Deserialize;
+use std::{collections::HashMap, sync::LazyLock};
+
+use crate::models::AddressComponents;
+
+static ADDRESS_FORMATS: LazyLock<AddressFormats> = LazyLock::new(|| {
+    toml::from_str(include_str!("address_formats.toml")).expect("bundled address formats are valid")
+});
+
+/// Country templates bundled with the crate, keyed by ISO 3166-1 alpha-2 code
+#[derive(Debug, Deserialize)]
+#[serde(transparent)]
+struct AddressFormats(HashMap<String, CountryFormat>);
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CountryFormat {
+    /// Template lines with `{component}` placeholders
+    lines: Option<Vec<String>>,
+    /// Country whose template this one reuses
+    #[serde(rename = "use")]
+    same_as: Option<String>,
+}
+
+/// Postal address rendered from components
+pub struct FormattedAddress {
+    /// Template that was applied: the country code or "default"
+    pub template: String,
+    /// Non-empty address lines in postal order
+    pub lines: Vec<String>,
+}
+
+/// Render address components using the template for a country
+///
+/// With `uppercase`, the lines follow postal style: uppercased with periods removed.
+pub fn format_address(
+    components: &AddressComponents,
+    country: &str,
+    uppercase: bool,
+) -> FormattedAddress {
+    let (template, lines) = template_for(&country.trim().to_uppercase());
+
+    let lines = lines
+        .iter()
+        .filter_map(|line| render_line(line, components))
+        .map(|line| {
+            if uppercase {
+                line.to_uppercase().replace('.', "")
+            } else {
+                line
+            }
+        })
+        .collect();
+
+    FormattedAddress {
+        template: template.to_string(),
+        lines,
+    }
+}
+
+/// Resolve the template lines for a country, following `use` references
+fn template_for(country: &str) -> (&'static str, &'static [String]) {
+    let formats = &ADDRESS_FORMATS.0;
+    let (mut code, mut format) = match formats.get_key_value(country) {
+        Some((code, format)) => (code.as_str(), format),
+        None => ("default", &formats["default"]),
+    };
+
+    // Bounded so a reference cycle in the bundled file cannot loop forever
+    for _ in 0..formats.len() {
+        if let Some(ref lines) = format.lines {
+            return (code, lines);
+        }
+        match format
+            .same_as
+            .as_deref()
+            .and_then(|other| formats.get_key_value(other))
+        {
+            Some((other, other_format)) => {
+                code = other.as_str();
+                format = other_format;
+            }
+            None => break,
+        }
+    }
+
+    let default = formats["default"].lines.as_deref().unwrap_or_default();
+    ("default", default)
+}
+
+/// Fill in a template line, or `None` if none of its components are present
+fn render_line(template: &str, components: &AddressComponents) -> Option<String> {
+    let mut rendered = String::new();
+    let mut filled = false;
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        rendered.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find('}') else {
+            rest = &rest[open..];
+            break;
+        };
+
+        let name = &rest[open + 1..open + close];
+        if let Some(value) = components
+            .get(name)
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+        {
+            rendered.push_str(value);
+            filled = true;
+        }
+        rest = &rest[open + close + 1..];
+    }
+    rendered.push_str(rest);
+
+    filled.then(|| clean_separators(&rendered))
+}
+
+/// Tidy separators left behind by missing components
+fn clean_separators(line: &str) -> String {
+    let mut line = line
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(" ,", ",");
+    while line.contains(",,") {
+        line = line.replace(",,", ",");
+    }
+
+    line.trim_matches(|c: char| c == ',' || c == '-' || c.is_whitespace())
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn components() -> AddressComponents {
+        AddressComponents {
+            house_number: Some("781".to_string()),
+            road: Some("Franklin Ave.".to_string()),
+            city: Some("Brooklyn".to_string()),
+            state: Some("NY".to_string()),
+            postcode: Some("11216".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn lines_without_components_are_dropped() {
+        let formatted = format_address(&components(), "us", false);
+
+        assert_eq!(formatted.template, "US");
+        assert_eq!(formatted.lines, ["781 Franklin Ave.", "Brooklyn, NY 11216"]);
+    }
+
+    #[test]
+    fn uppercase_follows_postal_style() {
+        let formatted = format_address(&components(), "US", true);
+
+        assert_eq!(formatted.lines, ["781 FRANKLIN AVE", "BROOKLYN, NY 11216"]);
+    }
+
+    #[test]
+    fn shared_and_unknown_templates_resolve() {
+        let components = AddressComponents {
+            road: Some("Stephansplatz".to_string()),
+            house_number: Some("1".to_string()),
+            postcode: Some("1010".to_string()),
+            city: Some("Wien".to_string()),
+            ..Default::default()
+        };
+
+        let austria = format_address(&components, "AT", false);
+        assert_eq!(austria.template, "DE");
+        assert_eq!(austria.lines, ["Stephansplatz 1", "1010 Wien"]);
+
+        assert_eq!(format_address(&components, "ZZ", false).template, "default");
+    }
+
+    #[test]
+    fn empty_components_count_as_missing() {
+        let components = AddressComponents {
+            city: Some("  ".to_string()),
+            state: Some("NY".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            render_line("{city}, {state}", &components).as_deref(),
+            Some("NY")
+        );
+        assert_eq!(render_line("{unit} {po_box}", &components), None);
+        assert_eq!(render_line("{city}", &components), None);
+    }
+
+    #[test]
+    fn unknown_placeholders_and_unclosed_braces_are_kept_as_text() {
+        let components = components();
+
+        assert_eq!(
+            render_line("{city} {nothing}", &components).as_deref(),
+            Some("Brooklyn")
+        );
+        assert_eq!(
+            render_line("{city} {state", &components).as_deref(),
+            Some("Brooklyn {state")
+        );
+    }
+
+    #[test]
+    fn separators_left_by_missing_components_are_removed() {
+        assert_eq!(clean_separators(", NY 11216"), "NY 11216");
+        assert_eq!(clean_separators("Brooklyn, , NY"), "Brooklyn, NY");
+        assert_eq!(clean_separators("Brooklyn,,, NY"), "Brooklyn, NY");
+        assert_eq!(clean_separators("  Brooklyn  ,\tNY  "), "Brooklyn, NY");
+        assert_eq!(clean_separators("- Brooklyn -"), "Brooklyn");
+    }
+}
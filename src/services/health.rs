@@ -0,0 +1,41 @@
+use std::{
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
+
+use crate::models::MemoryInfo;
+
+static STARTED_AT: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+/// Record the process start time; call once early during startup
+pub fn mark_started() {
+    LazyLock::force(&STARTED_AT);
+}
+
+/// Time since the process started
+pub fn uptime() -> Duration {
+    STARTED_AT.elapsed()
+}
+
+/// Resident memory of this process and memory available on the host
+///
+/// Only supported on Linux, where the values are read from `/proc`.
+pub fn memory_info() -> Option<MemoryInfo> {
+    let used_kb = read_proc_kb("/proc/self/status", "VmRSS:")?;
+    let available_kb = read_proc_kb("/proc/meminfo", "MemAvailable:")?;
+
+    Some(MemoryInfo {
+        used_bytes: used_kb * 1024,
+        available_bytes: available_kb * 1024,
+    })
+}
+
+/// Read a `Key:   1234 kB` line from a `/proc` file
+fn read_proc_kb(path: &str, key: &str) -> Option<u64> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    contents
+        .lines()
+        .find_map(|line| line.strip_prefix(key))
+        .and_then(|value| value.split_whitespace().next())
+        .and_then(|value| value.parse().ok())
+}
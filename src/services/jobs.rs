@@ -0,0 +1,1041 @@
+use anyhow::Context;
+use axum::body::Body;
+use futures_util::TryStreamExt;
+use http_body_util::LengthLimitError;
+use std::{
+    collections::HashMap,
+    fs::File,
+    future::Future,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock, RwLock},
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncWriteExt, BufWriter},
+    sync::{mpsc, Mutex},
+};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    config::{JobsConfig, ValidationConfig},
+    error::{ApiError, ApiResult},
+    models::*,
+    services::LibPostalService,
+};
+
+static JOB_MANAGER: OnceLock<JobManager> = OnceLock::new();
+
+/// Job state file inside each job directory
+const STATE_FILE: &str = "job.json";
+
+/// Result file inside each job directory
+const RESULT_FILE: &str = "result.ndjson";
+
+/// How often progress is written back to the state file
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Pause before retrying a row rejected because the worker queue was full
+const BUSY_BACKOFF: Duration = Duration::from_millis(50);
+
+/// Pause while interactive requests are waiting for a worker
+const INTERACTIVE_BACKOFF: Duration = Duration::from_millis(10);
+
+/// Longest a row waits for a free worker before its job fails
+const BUSY_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Longest time between sweeps for expired jobs
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Rows buffered between the file reader and the job runner
+const ROW_BUFFER: usize = 256;
+
+/// Bulk file jobs stored on local disk and processed in the background
+///
+/// Each job lives in its own directory holding the uploaded file, a `job.json`
+/// state file and the NDJSON result. Jobs that were queued or running when the
+/// process stopped are restarted from the beginning on the next startup.
+/// Completed and failed jobs are deleted once the retention period has passed.
+///
+/// Rows go through the shared worker pool one at a time per job and skip the
+/// result cache. A job also waits whenever interactive requests are queued for
+/// a worker, so bulk work only uses spare capacity. If the workers stay busy
+/// for [`BUSY_TIMEOUT`] the job fails rather than waiting forever.
+pub struct JobManager {
+    dir: PathBuf,
+    limits: ValidationConfig,
+    retention: Option<Duration>,
+    queue: mpsc::UnboundedSender<String>,
+    jobs: RwLock<HashMap<String, JobStatus>>,
+}
+
+/// A row read from an input file
+struct JobRow {
+    index: usize,
+    id: Option<String>,
+    address: Result<String, String>,
+}
+
+impl JobManager {
+    /// Load jobs from disk and start the job runners
+    pub async fn initialize(config: &JobsConfig, limits: &ValidationConfig) -> anyhow::Result<()> {
+        tokio::fs::create_dir_all(&config.dir)
+            .await
+            .with_context(|| format!("Unable to create jobs directory {}", config.dir.display()))?;
+
+        let (manager, receiver) = JobManager::new(config, limits);
+        let pending = manager.restore().await?;
+        JOB_MANAGER
+            .set(manager)
+            .map_err(|_| anyhow::anyhow!("Job manager already initialized"))?;
+        let manager = JOB_MANAGER.get().expect("job manager was just set");
+
+        if !pending.is_empty() {
+            info!(jobs = pending.len(), "Resuming unfinished jobs");
+        }
+        for id in pending {
+            let _ = manager.queue.send(id);
+        }
+
+        let receiver = Arc::new(Mutex::new(receiver));
+        for _ in 0..config.concurrency {
+            let receiver = receiver.clone();
+            tokio::spawn(async move {
+                loop {
+                    let Some(id) = receiver.lock().await.recv().await else {
+                        break;
+                    };
+                    manager.run(&id).await;
+                }
+            });
+        }
+
+        if let Some(retention) = manager.retention {
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(retention.min(CLEANUP_INTERVAL));
+                loop {
+                    interval.tick().await;
+                    let removed = manager.remove_expired().await;
+                    if removed > 0 {
+                        info!(jobs = removed, "Removed expired jobs");
+                    }
+                }
+            });
+        }
+
+        info!(
+            dir = %config.dir.display(),
+            concurrency = config.concurrency,
+            retention_secs = config.retention_secs,
+            "Job runners started"
+        );
+        Ok(())
+    }
+
+    /// Create a job manager with no jobs, returning it with the receiving end of its queue
+    fn new(
+        config: &JobsConfig,
+        limits: &ValidationConfig,
+    ) -> (Self, mpsc::UnboundedReceiver<String>) {
+        let (queue, receiver) = mpsc::unbounded_channel();
+        let manager = JobManager {
+            dir: config.dir.clone(),
+            limits: limits.clone(),
+            retention: (config.retention_secs > 0)
+                .then(|| Duration::from_secs(config.retention_secs)),
+            queue,
+            jobs: RwLock::new(HashMap::new()),
+        };
+        (manager, receiver)
+    }
+
+    /// Get the global job manager
+    pub fn global() -> ApiResult<&'static JobManager> {
+        JOB_MANAGER
+            .get()
+            .ok_or_else(|| ApiError::ServiceUnavailable("Job manager not initialized".to_string()))
+    }
+
+    /// Store an uploaded file and queue a job for it
+    pub async fn create(&self, options: JobOptions, body: Body) -> ApiResult<JobStatus> {
+        let format = options.format.unwrap_or(JobInputFormat::Csv);
+        let id = Uuid::new_v4().to_string();
+        let job_dir = self.dir.join(&id);
+
+        tokio::fs::create_dir_all(&job_dir)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Unable to create job directory: {e}")))?;
+
+        let input = job_dir.join(input_file_name(format));
+        let stored = match store_upload(&input, body).await {
+            Ok(0) => Err(ApiError::InvalidInput("Uploaded file is empty".to_string())),
+            Ok(_) if format == JobInputFormat::Csv => {
+                check_csv_header(input.clone(), options.clone()).await
+            }
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = stored {
+            let _ = tokio::fs::remove_dir_all(&job_dir).await;
+            return Err(e);
+        }
+
+        let status = JobStatus {
+            id: id.clone(),
+            state: JobState::Queued,
+            options,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            started_at: None,
+            finished_at: None,
+            total_rows: None,
+            processed_rows: 0,
+            failed_rows: 0,
+            error: None,
+            result_url: None,
+        };
+
+        self.save(&status).await;
+        self.jobs
+            .write()
+            .map_err(|_| ApiError::Internal("Job table poisoned".to_string()))?
+            .insert(id.clone(), status.clone());
+        self.queue
+            .send(id)
+            .map_err(|_| ApiError::ServiceUnavailable("Job runners stopped".to_string()))?;
+
+        Ok(status)
+    }
+
+    /// Current status of a job
+    pub fn status(&self, id: &str) -> ApiResult<JobStatus> {
+        Uuid::parse_str(id).map_err(|_| not_found(id))?;
+
+        self.jobs
+            .read()
+            .map_err(|_| ApiError::Internal("Job table poisoned".to_string()))?
+            .get(id)
+            .cloned()
+            .ok_or_else(|| not_found(id))
+    }
+
+    /// Path of a completed job's result file
+    pub fn result_path(&self, id: &str) -> ApiResult<PathBuf> {
+        let status = self.status(id)?;
+        if status.state != JobState::Completed {
+            return Err(ApiError::Conflict(format!(
+                "Job {id} is {}; its result is not ready",
+                status.state.as_str()
+            )));
+        }
+
+        Ok(self.dir.join(id).join(RESULT_FILE))
+    }
+
+    /// Delete finished jobs older than the retention period, returning how many were removed
+    async fn remove_expired(&self) -> usize {
+        let Some(retention) = self.retention else {
+            return 0;
+        };
+        let Ok(retention) = chrono::Duration::from_std(retention) else {
+            return 0;
+        };
+        let cutoff = chrono::Utc::now() - retention;
+
+        let expired: Vec<String> = {
+            let Ok(mut jobs) = self.jobs.write() else {
+                return 0;
+            };
+            let expired = jobs
+                .values()
+                .filter(|status| matches!(status.state, JobState::Completed | JobState::Failed))
+                .filter(|status| {
+                    status
+                        .finished_at
+                        .as_deref()
+                        .and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok())
+                        .is_some_and(|at| at < cutoff)
+                })
+                .map(|status| status.id.clone())
+                .collect::<Vec<_>>();
+            for id in &expired {
+                jobs.remove(id);
+            }
+            expired
+        };
+
+        for id in &expired {
+            if let Err(e) = tokio::fs::remove_dir_all(self.dir.join(id)).await {
+                warn!(job_id = %id, error = %e, "Unable to delete expired job files");
+            }
+        }
+
+        expired.len()
+    }
+
+    /// Read job state files, returning the jobs that still need to run
+    async fn restore(&self) -> anyhow::Result<Vec<String>> {
+        let mut entries = tokio::fs::read_dir(&self.dir)
+            .await
+            .with_context(|| format!("Unable to read jobs directory {}", self.dir.display()))?;
+
+        let mut restored = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let state_file = entry.path().join(STATE_FILE);
+            let Ok(contents) = tokio::fs::read(&state_file).await else {
+                continue;
+            };
+
+            match serde_json::from_slice::<JobStatus>(&contents) {
+                Ok(status) => restored.push(status),
+                Err(e) => {
+                    warn!(file = %state_file.display(), error = %e, "Skipping unreadable job state")
+                }
+            }
+        }
+
+        restored.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+        let mut pending = Vec::new();
+        let mut jobs = self
+            .jobs
+            .write()
+            .map_err(|_| anyhow::anyhow!("Job table poisoned"))?;
+        for mut status in restored {
+            if matches!(status.state, JobState::Queued | JobState::Running) {
+                status.state = JobState::Queued;
+                pending.push(status.id.clone());
+            }
+            jobs.insert(status.id.clone(), status);
+        }
+
+        Ok(pending)
+    }
+
+    /// Process a queued job and record how it ended
+    async fn run(&self, id: &str) {
+        let started = self
+            .update(id, |status| {
+                status.state = JobState::Running;
+                status.started_at = Some(chrono::Utc::now().to_rfc3339());
+                status.finished_at = None;
+                status.total_rows = None;
+                status.processed_rows = 0;
+                status.failed_rows = 0;
+                status.error = None;
+                status.result_url = None;
+            })
+            .await;
+        let Some(status) = started else {
+            return;
+        };
+
+        info!(job_id = %id, operation = ?status.options.operation, "Job started");
+
+        match self.process(id, &status.options).await {
+            Ok(()) => {
+                let finished = self
+                    .update(id, |status| {
+                        status.state = JobState::Completed;
+                        status.finished_at = Some(chrono::Utc::now().to_rfc3339());
+                        status.result_url = Some(format!("/api/v1/jobs/{id}/result"));
+                    })
+                    .await;
+                if let Some(status) = finished {
+                    info!(
+                        job_id = %id,
+                        processed_rows = status.processed_rows,
+                        failed_rows = status.failed_rows,
+                        "Job completed"
+                    );
+                }
+            }
+            Err(e) => {
+                error!(job_id = %id, error = %e, "Job failed");
+                self.update(id, |status| {
+                    status.state = JobState::Failed;
+                    status.finished_at = Some(chrono::Utc::now().to_rfc3339());
+                    status.error = Some(e.public_message());
+                })
+                .await;
+            }
+        }
+    }
+
+    /// Run every row of a job's input and write the result file
+    async fn process(&self, id: &str, options: &JobOptions) -> ApiResult<()> {
+        let service = LibPostalService::global()?;
+        let format = options.format.unwrap_or(JobInputFormat::Csv);
+        let job_dir = self.dir.join(id);
+        let input = job_dir.join(input_file_name(format));
+
+        let total_rows = {
+            let input = input.clone();
+            tokio::task::spawn_blocking(move || count_rows(&input, format))
+                .await
+                .map_err(|e| ApiError::Internal(format!("Row count failed: {e}")))??
+        };
+        self.update(id, |status| status.total_rows = Some(total_rows))
+            .await;
+
+        let (sender, mut rows) = mpsc::channel(ROW_BUFFER);
+        let reader = {
+            let options = options.clone();
+            tokio::task::spawn_blocking(move || read_rows(&input, format, &options, sender))
+        };
+
+        let result = tokio::fs::File::create(job_dir.join(RESULT_FILE))
+            .await
+            .map_err(|e| ApiError::Internal(format!("Unable to create result file: {e}")))?;
+        let mut result = BufWriter::new(result);
+
+        let mut processed_rows = 0;
+        let mut failed_rows = 0;
+        let mut last_report = Instant::now();
+
+        while let Some(row) = rows.recv().await {
+            // Leave workers to interactive requests whenever they are queueing
+            wait_for_interactive(|| service.queue_metrics().queued, BUSY_TIMEOUT).await?;
+
+            let (line, failed) = self.process_row(&service, options, row).await?;
+            result
+                .write_all(&line)
+                .await
+                .map_err(|e| ApiError::Internal(format!("Unable to write result: {e}")))?;
+
+            processed_rows += 1;
+            if failed {
+                failed_rows += 1;
+            }
+
+            if last_report.elapsed() >= PROGRESS_INTERVAL {
+                self.update(id, |status| {
+                    status.processed_rows = processed_rows;
+                    status.failed_rows = failed_rows;
+                })
+                .await;
+                last_report = Instant::now();
+            }
+        }
+
+        result
+            .flush()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Unable to write result: {e}")))?;
+        reader
+            .await
+            .map_err(|e| ApiError::Internal(format!("Input reader failed: {e}")))??;
+
+        self.update(id, |status| {
+            status.processed_rows = processed_rows;
+            status.failed_rows = failed_rows;
+        })
+        .await;
+
+        Ok(())
+    }
+
+    /// Process one row, returning its NDJSON result line and whether it failed
+    async fn process_row(
+        &self,
+        service: &LibPostalService,
+        options: &JobOptions,
+        row: JobRow,
+    ) -> ApiResult<(Vec<u8>, bool)> {
+        let (line, failed) = match options.operation {
+            JobOperation::Parse => {
+                let request = options.parse_request(row.address.clone().unwrap_or_default());
+                let outcome = match row.address {
+                    Ok(_) => match request.validate(&self.limits) {
+                        Ok(()) => {
+                            retry_when_busy(BUSY_TIMEOUT, || {
+                                service.parse_address_uncached(
+                                    &request.address,
+                                    request.language.as_deref(),
+                                    request.country.as_deref(),
+                                )
+                            })
+                            .await?
+                        }
+                        Err(errors) => Err(errors.into()),
+                    },
+                    Err(msg) => Err(ApiError::InvalidInput(msg)),
+                };
+
+                let (components, error) = match outcome {
+                    Ok(parsed) => (Some(AddressComponents::from(parsed)), None),
+                    Err(err) => (None, Some(BatchItemError::from(&err))),
+                };
+
+                let failed = error.is_some();
+                let line = serde_json::to_vec(&BatchParseItemResult {
+                    index: row.index,
+                    id: row.id,
+                    hints: LibPostalService::resolve_hints(
+                        request.language.as_deref(),
+                        request.country.as_deref(),
+                    ),
+                    original: request.address,
+                    components,
+                    tokens: None,
+                    error,
+                });
+                (line, failed)
+            }
+            JobOperation::Normalize => {
+                let request = options.normalize_request(row.address.clone().unwrap_or_default());
+                let outcome = match row.address {
+                    Ok(_) => match request.validate(&self.limits) {
+                        Ok(()) => {
+                            retry_when_busy(BUSY_TIMEOUT, || {
+                                service.normalize_address_uncached(
+                                    &request.address,
+                                    request.level.as_deref(),
+                                    request.languages.as_deref(),
+                                )
+                            })
+                            .await?
+                        }
+                        Err(errors) => Err(errors.into()),
+                    },
+                    Err(msg) => Err(ApiError::InvalidInput(msg)),
+                };
+
+                let (expansions, error) = match outcome {
+                    Ok(normalized) => (Some(normalized.expansions), None),
+                    Err(err) => (None, Some(BatchItemError::from(&err))),
+                };
+
+                let failed = error.is_some();
+                let line = serde_json::to_vec(&BatchNormalizeItemResult {
+                    index: row.index,
+                    id: row.id,
+                    original: request.address,
+                    expansion_count: expansions.as_ref().map(Vec::len),
+                    expansions,
+                    error,
+                });
+                (line, failed)
+            }
+        };
+
+        let mut line =
+            line.map_err(|e| ApiError::Internal(format!("Unable to encode result: {e}")))?;
+        line.push(b'\n');
+        Ok((line, failed))
+    }
+
+    /// Apply a change to a job's status and persist it
+    async fn update(&self, id: &str, change: impl FnOnce(&mut JobStatus)) -> Option<JobStatus> {
+        let status = {
+            let mut jobs = self.jobs.write().ok()?;
+            let status = jobs.get_mut(id)?;
+            change(status);
+            status.clone()
+        };
+
+        self.save(&status).await;
+        Some(status)
+    }
+
+    /// Write a job's state file, replacing the previous one atomically
+    async fn save(&self, status: &JobStatus) {
+        let job_dir = self.dir.join(&status.id);
+        let temp = job_dir.join(format!("{STATE_FILE}.tmp"));
+
+        let written = async {
+            let contents = serde_json::to_vec_pretty(status)?;
+            tokio::fs::write(&temp, contents).await?;
+            tokio::fs::rename(&temp, job_dir.join(STATE_FILE)).await?;
+            anyhow::Ok(())
+        };
+
+        if let Err(e) = written.await {
+            error!(job_id = %status.id, error = %e, "Unable to save job state");
+        }
+    }
+}
+
+fn not_found(id: &str) -> ApiError {
+    ApiError::NotFound(format!("Job {id} not found"))
+}
+
+fn input_file_name(format: JobInputFormat) -> &'static str {
+    match format {
+        JobInputFormat::Csv => "input.csv",
+        JobInputFormat::Ndjson => "input.ndjson",
+    }
+}
+
+/// Wait while interactive requests are queued for a worker, up to `timeout`
+async fn wait_for_interactive(queued: impl Fn() -> usize, timeout: Duration) -> ApiResult<()> {
+    let deadline = Instant::now() + timeout;
+    while queued() > 0 {
+        if Instant::now() >= deadline {
+            return Err(workers_busy(timeout));
+        }
+        tokio::time::sleep(INTERACTIVE_BACKOFF).await;
+    }
+    Ok(())
+}
+
+/// Call libpostal, waiting and retrying while the worker queue is full
+///
+/// The inner result is the call's own outcome. The outer error means the queue
+/// stayed full for `timeout` and the job should fail.
+async fn retry_when_busy<T, F, Fut>(timeout: Duration, mut call: F) -> ApiResult<ApiResult<T>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ApiResult<T>>,
+{
+    let deadline = Instant::now() + timeout;
+    loop {
+        match call().await {
+            Err(ApiError::QueueFull) if Instant::now() < deadline => {
+                tokio::time::sleep(BUSY_BACKOFF).await
+            }
+            Err(ApiError::QueueFull) => return Err(workers_busy(timeout)),
+            result => return Ok(result),
+        }
+    }
+}
+
+fn workers_busy(timeout: Duration) -> ApiError {
+    ApiError::ServiceUnavailable(format!(
+        "Workers stayed busy for {} seconds",
+        timeout.as_secs()
+    ))
+}
+
+/// Stream an uploaded body to disk, returning the number of bytes written
+async fn store_upload(path: &Path, body: Body) -> ApiResult<u64> {
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Unable to store upload: {e}")))?;
+
+    let mut stream = body.into_data_stream();
+    let mut written = 0;
+    while let Some(chunk) = stream.try_next().await.map_err(upload_error)? {
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Unable to store upload: {e}")))?;
+        written += chunk.len() as u64;
+    }
+
+    file.flush()
+        .await
+        .map_err(|e| ApiError::Internal(format!("Unable to store upload: {e}")))?;
+    Ok(written)
+}
+
+/// Error for an upload body that could not be read, telling an oversized file apart
+fn upload_error(err: axum::Error) -> ApiError {
+    let too_large = std::iter::successors(Some(&err as &dyn std::error::Error), |e| e.source())
+        .any(|e| e.is::<LengthLimitError>());
+
+    if too_large {
+        ApiError::PayloadTooLarge("Uploaded file exceeds the upload size limit".to_string())
+    } else {
+        ApiError::InvalidInput(format!("Unable to read upload: {err}"))
+    }
+}
+
+/// Check that a CSV upload has the configured address and id columns
+async fn check_csv_header(path: PathBuf, options: JobOptions) -> ApiResult<()> {
+    tokio::task::spawn_blocking(move || {
+        let mut reader = csv::Reader::from_path(&path)
+            .map_err(|e| ApiError::InvalidInput(format!("Invalid CSV file: {e}")))?;
+        let headers = reader
+            .headers()
+            .map_err(|e| ApiError::InvalidInput(format!("Invalid CSV header: {e}")))?;
+
+        for column in std::iter::once(&options.column).chain(options.id_column.as_ref()) {
+            if !headers.iter().any(|header| header == column) {
+                return Err(ApiError::InvalidInput(format!(
+                    "Column '{column}' not found in CSV header"
+                )));
+            }
+        }
+
+        Ok(())
+    })
+    .await
+    .map_err(|e| ApiError::Internal(format!("Header check failed: {e}")))?
+}
+
+/// Count the rows of an input file the same way `read_rows` reads them
+fn count_rows(path: &Path, format: JobInputFormat) -> ApiResult<u64> {
+    let io_error = |e: std::io::Error| ApiError::Internal(format!("Unable to read input: {e}"));
+
+    let count = match format {
+        JobInputFormat::Csv => csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_path(path)
+            .map_err(|e| ApiError::InvalidInput(format!("Invalid CSV file: {e}")))?
+            .byte_records()
+            .count(),
+        JobInputFormat::Ndjson => {
+            let mut count = 0;
+            for line in BufReader::new(File::open(path).map_err(io_error)?).lines() {
+                if !line.map_err(io_error)?.trim().is_empty() {
+                    count += 1;
+                }
+            }
+            count
+        }
+    };
+
+    Ok(count as u64)
+}
+
+/// Read rows from an input file and send them to the job runner
+///
+/// Rows that cannot be read are still sent, with an error in place of the
+/// address, so they show up in the result file.
+fn read_rows(
+    path: &Path,
+    format: JobInputFormat,
+    options: &JobOptions,
+    rows: mpsc::Sender<JobRow>,
+) -> ApiResult<()> {
+    let io_error = |e: std::io::Error| ApiError::Internal(format!("Unable to read input: {e}"));
+
+    match format {
+        JobInputFormat::Csv => {
+            let mut reader = csv::ReaderBuilder::new()
+                .flexible(true)
+                .from_path(path)
+                .map_err(|e| ApiError::InvalidInput(format!("Invalid CSV file: {e}")))?;
+            let headers = reader
+                .headers()
+                .map_err(|e| ApiError::InvalidInput(format!("Invalid CSV header: {e}")))?
+                .clone();
+            let address_index = headers.iter().position(|h| h == options.column);
+            let id_index = options
+                .id_column
+                .as_ref()
+                .and_then(|column| headers.iter().position(|h| h == column));
+
+            for (index, record) in reader.records().enumerate() {
+                let row = match record {
+                    Ok(record) => JobRow {
+                        index,
+                        id: id_index.and_then(|i| record.get(i)).map(str::to_string),
+                        address: address_index
+                            .and_then(|i| record.get(i))
+                            .map(str::to_string)
+                            .ok_or_else(|| format!("Missing column '{}'", options.column)),
+                    },
+                    Err(e) => JobRow {
+                        index,
+                        id: None,
+                        address: Err(format!("Invalid CSV row: {e}")),
+                    },
+                };
+
+                if rows.blocking_send(row).is_err() {
+                    break;
+                }
+            }
+        }
+        JobInputFormat::Ndjson => {
+            let lines = BufReader::new(File::open(path).map_err(io_error)?).lines();
+            let mut index = 0;
+
+            for line in lines {
+                let line = line.map_err(io_error)?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let row = match serde_json::from_str::<serde_json::Value>(&line) {
+                    Ok(value) => JobRow {
+                        index,
+                        id: options
+                            .id_column
+                            .as_ref()
+                            .and_then(|column| value.get(column))
+                            .map(|id| match id {
+                                serde_json::Value::String(id) => id.clone(),
+                                other => other.to_string(),
+                            }),
+                        address: value
+                            .get(&options.column)
+                            .and_then(serde_json::Value::as_str)
+                            .map(str::to_string)
+                            .ok_or_else(|| format!("Missing string field '{}'", options.column)),
+                    },
+                    Err(e) => JobRow {
+                        index,
+                        id: None,
+                        address: Err(format!("Invalid JSON line: {e}")),
+                    },
+                };
+
+                if rows.blocking_send(row).is_err() {
+                    break;
+                }
+                index += 1;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// Job manager over a fresh directory, removed again on drop
+    struct TestJobs {
+        manager: JobManager,
+        queue: mpsc::UnboundedReceiver<String>,
+    }
+
+    impl TestJobs {
+        fn new(retention_secs: u64) -> Self {
+            let config = JobsConfig {
+                dir: std::env::temp_dir().join(format!("libpostal-api-jobs-{}", Uuid::new_v4())),
+                retention_secs,
+                ..JobsConfig::default()
+            };
+            std::fs::create_dir_all(&config.dir).unwrap();
+            let (manager, queue) = JobManager::new(&config, &ValidationConfig::default());
+            Self { manager, queue }
+        }
+    }
+
+    impl Drop for TestJobs {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.manager.dir);
+        }
+    }
+
+    fn options(format: JobInputFormat) -> JobOptions {
+        JobOptions {
+            operation: JobOperation::Parse,
+            format: Some(format),
+            column: "address".to_string(),
+            id_column: Some("id".to_string()),
+            language: None,
+            country: None,
+            level: None,
+            languages: None,
+        }
+    }
+
+    fn write_input(contents: &str) -> (TestJobs, PathBuf) {
+        let jobs = TestJobs::new(0);
+        let path = jobs.manager.dir.join("input");
+        std::fs::write(&path, contents).unwrap();
+        (jobs, path)
+    }
+
+    fn read_all(path: &Path, format: JobInputFormat) -> Vec<JobRow> {
+        let (sender, mut receiver) = mpsc::channel(ROW_BUFFER);
+        read_rows(path, format, &options(format), sender).unwrap();
+
+        let mut rows = Vec::new();
+        while let Ok(row) = receiver.try_recv() {
+            rows.push(row);
+        }
+        rows
+    }
+
+    #[tokio::test]
+    async fn busy_calls_are_retried_until_the_timeout() {
+        let calls = AtomicUsize::new(0);
+        let result = retry_when_busy(Duration::from_secs(5), || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(ApiError::QueueFull),
+                _ => Ok("parsed"),
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap().unwrap(), "parsed");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let result = retry_when_busy(Duration::ZERO, || async {
+            Err::<(), _>(ApiError::QueueFull)
+        })
+        .await;
+        assert!(matches!(result, Err(ApiError::ServiceUnavailable(_))));
+    }
+
+    #[tokio::test]
+    async fn other_call_errors_belong_to_the_row() {
+        let result = retry_when_busy(Duration::from_secs(5), || async {
+            Err::<(), _>(ApiError::InvalidInput("bad row".to_string()))
+        })
+        .await;
+        assert!(matches!(result, Ok(Err(ApiError::InvalidInput(_)))));
+    }
+
+    #[tokio::test]
+    async fn waiting_for_interactive_requests_is_bounded() {
+        let queued = AtomicUsize::new(2);
+        let waited = wait_for_interactive(
+            || queued.fetch_sub(1, Ordering::SeqCst).saturating_sub(1),
+            Duration::from_secs(5),
+        )
+        .await;
+        assert!(waited.is_ok());
+
+        let waited = wait_for_interactive(|| 1, Duration::from_millis(30)).await;
+        assert!(matches!(waited, Err(ApiError::ServiceUnavailable(_))));
+    }
+
+    #[tokio::test]
+    async fn oversized_uploads_are_payload_too_large() {
+        let jobs = TestJobs::new(0);
+        let path = jobs.manager.dir.join("upload");
+
+        let body = Body::new(http_body_util::Limited::new(Body::from(vec![b'a'; 64]), 16));
+        let err = store_upload(&path, body).await.unwrap_err();
+        assert_eq!(err.status_code(), axum::http::StatusCode::PAYLOAD_TOO_LARGE);
+
+        let written = store_upload(&path, Body::from("address\n")).await.unwrap();
+        assert_eq!(written, 8);
+    }
+
+    #[tokio::test]
+    async fn created_jobs_are_queued_without_a_result() {
+        let mut jobs = TestJobs::new(0);
+        let body = Body::from("id,address\n1,781 Franklin Ave\n");
+
+        let status = jobs
+            .manager
+            .create(options(JobInputFormat::Csv), body)
+            .await
+            .unwrap();
+        assert_eq!(status.state, JobState::Queued);
+        assert_eq!(jobs.queue.recv().await.as_deref(), Some(status.id.as_str()));
+        assert!(jobs.manager.dir.join(&status.id).join(STATE_FILE).is_file());
+
+        assert_eq!(jobs.manager.status(&status.id).unwrap().id, status.id);
+        assert!(matches!(
+            jobs.manager.result_path(&status.id),
+            Err(ApiError::Conflict(_))
+        ));
+        assert!(matches!(
+            jobs.manager.status("not-a-job"),
+            Err(ApiError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejected_uploads_leave_nothing_behind() {
+        let jobs = TestJobs::new(0);
+
+        let empty = jobs
+            .manager
+            .create(options(JobInputFormat::Csv), Body::empty())
+            .await;
+        assert!(matches!(empty, Err(ApiError::InvalidInput(_))));
+
+        let missing_column = jobs
+            .manager
+            .create(
+                options(JobInputFormat::Csv),
+                Body::from("id,street\n1,Main St\n"),
+            )
+            .await;
+        assert!(matches!(missing_column, Err(ApiError::InvalidInput(_))));
+
+        assert_eq!(std::fs::read_dir(&jobs.manager.dir).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn finished_jobs_are_removed_after_the_retention_period() {
+        let jobs = TestJobs::new(60);
+        let long_ago = (chrono::Utc::now() - chrono::Duration::hours(1)).to_rfc3339();
+
+        let mut ids = Vec::new();
+        for (state, finished_at) in [
+            (JobState::Completed, Some(long_ago.clone())),
+            (JobState::Failed, Some(long_ago.clone())),
+            (JobState::Completed, Some(chrono::Utc::now().to_rfc3339())),
+            (JobState::Running, None),
+        ] {
+            let status = jobs
+                .manager
+                .create(
+                    options(JobInputFormat::Ndjson),
+                    Body::from("{\"address\":\"x\"}\n"),
+                )
+                .await
+                .unwrap();
+            jobs.manager
+                .update(&status.id, |status| {
+                    status.state = state;
+                    status.finished_at = finished_at;
+                })
+                .await;
+            ids.push(status.id);
+        }
+
+        assert_eq!(jobs.manager.remove_expired().await, 2);
+        for (i, id) in ids.iter().enumerate() {
+            let kept = i >= 2;
+            assert_eq!(jobs.manager.status(id).is_ok(), kept);
+            assert_eq!(jobs.manager.dir.join(id).exists(), kept);
+        }
+    }
+
+    #[tokio::test]
+    async fn jobs_are_kept_without_a_retention_period() {
+        let jobs = TestJobs::new(0);
+        let status = jobs
+            .manager
+            .create(
+                options(JobInputFormat::Ndjson),
+                Body::from("{\"address\":\"x\"}\n"),
+            )
+            .await
+            .unwrap();
+        jobs.manager
+            .update(&status.id, |status| {
+                status.state = JobState::Completed;
+                status.finished_at = Some("2000-01-01T00:00:00Z".to_string());
+            })
+            .await;
+
+        assert_eq!(jobs.manager.remove_expired().await, 0);
+        assert!(jobs.manager.status(&status.id).is_ok());
+    }
+
+    #[test]
+    fn ndjson_rows_skip_blank_lines_and_keep_bad_ones() {
+        let (_jobs, path) =
+            write_input("{\"id\":7,\"address\":\"1 Main St\"}\n\n{not json}\n{\"id\":\"b\"}\n");
+
+        assert_eq!(count_rows(&path, JobInputFormat::Ndjson).unwrap(), 3);
+
+        let rows = read_all(&path, JobInputFormat::Ndjson);
+        assert_eq!(rows.len(), 3);
+        assert_eq!((rows[0].index, rows[0].id.as_deref()), (0, Some("7")));
+        assert_eq!(rows[0].address.as_deref(), Ok("1 Main St"));
+        assert!(rows[1]
+            .address
+            .as_ref()
+            .unwrap_err()
+            .starts_with("Invalid JSON line"));
+        assert_eq!((rows[2].index, rows[2].id.as_deref()), (2, Some("b")));
+        assert!(rows[2].address.is_err());
+    }
+
+    #[test]
+    fn csv_rows_report_short_records() {
+        let (_jobs, path) = write_input("id,address\n1,1 Main St\n2\n");
+
+        assert_eq!(count_rows(&path, JobInputFormat::Csv).unwrap(), 2);
+
+        let rows = read_all(&path, JobInputFormat::Csv);
+        assert_eq!(rows[0].id.as_deref(), Some("1"));
+        assert_eq!(rows[0].address.as_deref(), Ok("1 Main St"));
+        assert_eq!(rows[1].address, Err("Missing column 'address'".to_string()));
+    }
+}
@@ -1,39 +1,109 @@
-use crate::error::{ApiError, ApiResult};
-use libpostal_rs::{LibPostal, LibPostalConfig, NormalizationLevel};
-use std::sync::Arc;
-use tokio::sync::{OnceCell, Mutex};
-use tracing::{error, info, warn};
+use crate::{
+    config::{CacheConfig, DataConfig, WorkerConfig},
+    error::{ApiError, ApiResult},
+    models::{
+        AppliedHints, DataIntegrityStatus, DataVersion, ModelStatus, QueueMetrics, SelfTestResult,
+    },
+    services::{
+        cache::{Cached, ResultCache},
+        metrics::Metrics,
+        worker_pool::WorkerPool,
+    },
+};
+use libpostal_rs::{
+    data::DataManager, Country, Language, LibPostal, LibPostalConfig, NormalizationLevel,
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock, RwLock},
+    time::{Duration, Instant},
+};
+use tracing::{debug, error, info, warn};
 
-/// LibPostal service wrapper for thread-safe access
-#[derive(Clone)]
+/// LibPostal service dispatching calls to a pool of blocking workers
+///
+/// libpostal keeps a single model in global state and can only replace it by
+/// tearing it down first, which would stop every call while the new one loads.
+/// The model is therefore loaded once, when the service is initialized, and
+/// serves for the life of the process; new data is checked out of process (see
+/// [`super::reload::ModelReloader`]) and put in service by a restart.
 pub struct LibPostalService {
-    postal: Arc<Mutex<LibPostal>>,
+    postal: Arc<LibPostal>,
+    model: LoadedModel,
+    pool: Arc<WorkerPool>,
+    cache: Arc<ResultCache>,
+    last_self_test: Arc<RwLock<Option<SelfTestResult>>>,
+}
+
+/// Where a model was loaded from and how that went
+#[derive(Clone)]
+struct LoadedModel {
+    data_dir: PathBuf,
+    load_time: Duration,
+    loaded_at: chrono::DateTime<chrono::Utc>,
+    data_integrity: DataIntegrityStatus,
 }
 
-static LIBPOSTAL_INSTANCE: OnceCell<LibPostalService> = OnceCell::const_new();
+static LIBPOSTAL_INSTANCE: OnceLock<Arc<LibPostalService>> = OnceLock::new();
 
 impl LibPostalService {
     /// Initialize the LibPostal service
-    pub async fn initialize() -> ApiResult<()> {
-        info!("Initializing LibPostal service...");
+    pub async fn initialize(
+        data: &DataConfig,
+        workers: &WorkerConfig,
+        cache: &CacheConfig,
+    ) -> ApiResult<()> {
+        info!(data_dir = %data.dir.display(), "Initializing LibPostal service...");
 
         let config = LibPostalConfig::builder()
-            .data_dir("./data")
-            .auto_download_data(true)
-            .verify_data_integrity(true)
+            .data_dir(&data.dir)
+            .auto_download_data(data.auto_download)
+            .verify_data_integrity(data.verify_integrity)
             .build();
 
+        let load_start = Instant::now();
         match LibPostal::with_config(config).await {
             Ok(postal) => {
+                let load_time = load_start.elapsed();
+                let data_integrity =
+                    Self::check_data_integrity(&data.dir, data.verify_integrity).await;
+
+                let pool = WorkerPool::new(workers);
+                info!(
+                    workers = pool.metrics().workers,
+                    queue_depth = workers.queue_depth,
+                    "LibPostal worker pool started"
+                );
+
+                let result_cache = ResultCache::new(cache);
+                info!(
+                    capacity = cache.capacity,
+                    ttl_secs = cache.ttl_secs,
+                    enabled = result_cache.is_enabled(),
+                    "Result cache configured"
+                );
+
                 let service = LibPostalService {
-                    postal: Arc::new(Mutex::new(postal)),
+                    postal: Arc::new(postal),
+                    model: LoadedModel {
+                        data_dir: data.dir.clone(),
+                        load_time,
+                        loaded_at: chrono::Utc::now(),
+                        data_integrity,
+                    },
+                    pool: Arc::new(pool),
+                    cache: Arc::new(result_cache),
+                    last_self_test: Arc::new(RwLock::new(None)),
                 };
 
-                LIBPOSTAL_INSTANCE.set(service).map_err(|_| {
+                LIBPOSTAL_INSTANCE.set(Arc::new(service)).map_err(|_| {
                     ApiError::Internal("Failed to set LibPostal instance".to_string())
                 })?;
 
-                info!("LibPostal service initialized successfully");
+                info!(
+                    load_time_ms = load_time.as_millis() as u64,
+                    "LibPostal service initialized successfully"
+                );
                 Ok(())
             }
             Err(e) => {
@@ -45,38 +115,140 @@ impl LibPostalService {
         }
     }
 
-    /// Get the global LibPostal service instance
-    pub fn global() -> ApiResult<&'static LibPostalService> {
-        LIBPOSTAL_INSTANCE.get().ok_or_else(|| {
+    /// Get the LibPostal service instance
+    pub fn global() -> ApiResult<Arc<LibPostalService>> {
+        LIBPOSTAL_INSTANCE.get().cloned().ok_or_else(|| {
             ApiError::ServiceUnavailable("LibPostal service not initialized".to_string())
         })
     }
 
-    /// Parse an address into components
+    /// Resolve the hints that will be passed to libpostal for a parse request
+    ///
+    /// Each hint is applied on its own; blank hints are dropped, languages are
+    /// lowercased and countries uppercased.
+    pub fn resolve_hints(language: Option<&str>, country: Option<&str>) -> AppliedHints {
+        AppliedHints {
+            language: language
+                .map(str::trim)
+                .filter(|lang| !lang.is_empty())
+                .map(|lang| Language::from_str(&lang.to_lowercase()).to_string()),
+            country: country
+                .map(str::trim)
+                .filter(|ctry| !ctry.is_empty())
+                .map(|ctry| Country::from_str(ctry).to_string().to_uppercase()),
+        }
+    }
+
+    /// Parse an address into components, serving repeated lookups from the cache
     pub async fn parse_address(
         &self,
         address: &str,
         language: Option<&str>,
         country: Option<&str>,
-    ) -> ApiResult<libpostal_rs::ParsedAddress> {
-        let postal = self.postal.lock().await;
-        match (language, country) {
-            (Some(lang), Some(ctry)) => postal
-                .parse_address_with_hints(address, Some(lang), Some(ctry))
-                .map_err(ApiError::from),
-            _ => postal.parse_address(address).map_err(ApiError::from),
+    ) -> ApiResult<Cached<libpostal_rs::ParsedAddress>> {
+        let hints = Self::resolve_hints(language, country);
+        let key = (ResultCache::address_key(address), hints.clone());
+
+        if let Some(parsed) = self.cache.parse.get(&key) {
+            return Ok(Cached {
+                value: parsed,
+                hit: true,
+            });
         }
+
+        let parsed = self.parse_with_hints(address, hints).await?;
+
+        self.cache.parse.insert(key, parsed.clone());
+        Ok(Cached {
+            value: parsed,
+            hit: false,
+        })
+    }
+
+    /// Parse an address without consulting or filling the result cache
+    ///
+    /// Used for bulk work that would otherwise evict interactive results.
+    pub async fn parse_address_uncached(
+        &self,
+        address: &str,
+        language: Option<&str>,
+        country: Option<&str>,
+    ) -> ApiResult<libpostal_rs::ParsedAddress> {
+        self.parse_with_hints(address, Self::resolve_hints(language, country))
+            .await
+    }
+
+    async fn parse_with_hints(
+        &self,
+        address: &str,
+        hints: AppliedHints,
+    ) -> ApiResult<libpostal_rs::ParsedAddress> {
+        let postal = self.postal.clone();
+        let address = address.to_string();
+
+        self.pool
+            .run("parse", move || {
+                if hints.is_empty() {
+                    postal.parse_address(&address).map_err(ApiError::from)
+                } else {
+                    postal
+                        .parse_address_with_hints(
+                            &address,
+                            hints.language.as_deref(),
+                            hints.country.as_deref(),
+                        )
+                        .map_err(ApiError::from)
+                }
+            })
+            .await
     }
 
-    /// Normalize an address
+    /// Normalize an address, serving repeated lookups from the cache
     pub async fn normalize_address(
         &self,
         address: &str,
         level: Option<&str>,
         languages: Option<&[String]>,
+    ) -> ApiResult<Cached<libpostal_rs::NormalizedAddress>> {
+        let key = (
+            ResultCache::address_key(address),
+            level.map(str::to_string),
+            languages.map(|langs| {
+                langs
+                    .iter()
+                    .map(|lang| lang.trim().to_lowercase())
+                    .collect()
+            }),
+        );
+
+        if let Some(mut normalized) = self.cache.normalize.get(&key) {
+            // The cached entry may have come from differently formatted input
+            normalized.original = address.to_string();
+            return Ok(Cached {
+                value: normalized,
+                hit: true,
+            });
+        }
+
+        let normalized = self
+            .normalize_address_uncached(address, level, languages)
+            .await?;
+
+        self.cache.normalize.insert(key, normalized.clone());
+        Ok(Cached {
+            value: normalized,
+            hit: false,
+        })
+    }
+
+    /// Normalize an address without consulting or filling the result cache
+    pub async fn normalize_address_uncached(
+        &self,
+        address: &str,
+        level: Option<&str>,
+        languages: Option<&[String]>,
     ) -> ApiResult<libpostal_rs::NormalizedAddress> {
-        let postal = self.postal.lock().await;
-        let mut normalizer = postal.normalizer();
+        let mut normalizer = self.postal.normalizer();
 
         // Set normalization level if provided
         if let Some(level_str) = level {
@@ -101,14 +273,40 @@ impl LibPostalService {
             normalizer = normalizer.with_languages(&language_types);
         }
 
-        normalizer.normalize(address).map_err(ApiError::from)
+        let address = address.to_string();
+        let normalized = self
+            .pool
+            .run("normalize", move || {
+                normalizer.normalize(&address).map_err(ApiError::from)
+            })
+            .await?;
+
+        Metrics::global()
+            .record_expansions(level.unwrap_or("default"), normalized.expansions.len());
+
+        Ok(normalized)
+    }
+
+    /// Whether parse and normalize results are cached
+    pub fn cache_enabled(&self) -> bool {
+        self.cache.is_enabled()
+    }
+
+    /// Drop all cached results, returning how many were removed
+    pub fn flush_cache(&self) -> usize {
+        self.cache.clear()
     }
 
     /// Check if the service is healthy
     pub async fn health_check(&self) -> ApiResult<()> {
         // Try a simple parse to verify LibPostal is working
-        let postal = self.postal.lock().await;
-        match postal.parse_address("test") {
+        let postal = self.postal.clone();
+        let result = self
+            .pool
+            .run("health_check", move || Ok(postal.parse_address("test")))
+            .await?;
+
+        match result {
             Ok(_) => Ok(()),
             Err(e) => {
                 error!("LibPostal health check failed: {}", e);
@@ -118,4 +316,168 @@ impl LibPostalService {
             }
         }
     }
+
+    /// Run a self-test parse and remember its outcome for readiness reports
+    ///
+    /// The test parse waits in the worker queue like any request. When the
+    /// queue is full the workers are busy answering requests, so the previous
+    /// outcome is kept rather than reporting the service as unready.
+    pub async fn run_self_test(&self) -> SelfTestResult {
+        let start_time = Instant::now();
+        let outcome = self.health_check().await;
+
+        if matches!(outcome, Err(ApiError::QueueFull)) {
+            if let Some(last) = self.last_self_test() {
+                debug!("Worker queue full, keeping the previous self-test result");
+                return last;
+            }
+        }
+
+        let result = SelfTestResult {
+            passed: outcome.is_ok(),
+            checked_at: chrono::Utc::now().to_rfc3339(),
+            duration_ms: start_time.elapsed().as_millis() as u64,
+            error: outcome.err().map(|e| e.to_string()),
+        };
+
+        if let Ok(mut last) = self.last_self_test.write() {
+            *last = Some(result.clone());
+        }
+
+        result
+    }
+
+    /// Outcome of the most recent self-test, if one has run
+    pub fn last_self_test(&self) -> Option<SelfTestResult> {
+        self.last_self_test
+            .read()
+            .ok()
+            .and_then(|last| last.clone())
+    }
+
+    /// Directory the LibPostal data was loaded from
+    pub fn data_dir(&self) -> &Path {
+        &self.model.data_dir
+    }
+
+    /// Time taken to load the LibPostal model
+    pub fn load_time(&self) -> Duration {
+        self.model.load_time
+    }
+
+    /// Result of the data integrity check run when the model was loaded
+    pub fn data_integrity(&self) -> DataIntegrityStatus {
+        self.model.data_integrity.clone()
+    }
+
+    /// Where the model came from, its data version and when it was loaded
+    pub fn model_status(&self) -> ModelStatus {
+        ModelStatus {
+            data_dir: self.model.data_dir.display().to_string(),
+            data_version: read_data_version(&self.model.data_dir),
+            loaded_at: self.model.loaded_at.to_rfc3339(),
+            load_time_ms: self.model.load_time.as_millis() as u64,
+            data_integrity: self.model.data_integrity.clone(),
+        }
+    }
+
+    /// Check the files in a data directory, unless verification is switched off
+    pub async fn check_data_integrity(dir: &Path, verify: bool) -> DataIntegrityStatus {
+        if !verify {
+            return DataIntegrityStatus {
+                status: "skipped".to_string(),
+                message: None,
+            };
+        }
+
+        let dir = dir.to_path_buf();
+        let outcome = tokio::task::spawn_blocking(move || {
+            DataManager::with_data_dir(dir)
+                .verify_data()
+                .map_err(|e| e.to_string())
+        })
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()));
+
+        match outcome {
+            Ok(()) => DataIntegrityStatus {
+                status: "verified".to_string(),
+                message: None,
+            },
+            Err(message) => {
+                warn!(error = %message, "LibPostal data integrity check failed");
+                DataIntegrityStatus {
+                    status: "failed".to_string(),
+                    message: Some(message),
+                }
+            }
+        }
+    }
+
+    /// Current load of the worker pool
+    pub fn queue_metrics(&self) -> QueueMetrics {
+        self.pool.metrics()
+    }
+}
+
+/// Versions recorded in a LibPostal data directory, where present
+pub fn read_data_version(data_dir: &Path) -> DataVersion {
+    let read = |file: &str| {
+        std::fs::read_to_string(data_dir.join(file))
+            .ok()
+            .map(|version| version.trim().to_string())
+            .filter(|version| !version.is_empty())
+    };
+
+    DataVersion {
+        base: read("base_data_file_version"),
+        parser: read("parser_model_file_version"),
+        language_classifier: read("language_classifier_model_file_version"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_hints_without_hints() {
+        let hints = LibPostalService::resolve_hints(None, None);
+
+        assert!(hints.is_empty());
+        assert_eq!(hints, AppliedHints::default());
+    }
+
+    #[test]
+    fn resolve_hints_with_language_only() {
+        let hints = LibPostalService::resolve_hints(Some("FR"), None);
+
+        assert!(!hints.is_empty());
+        assert_eq!(hints.language.as_deref(), Some("fr"));
+        assert_eq!(hints.country, None);
+    }
+
+    #[test]
+    fn resolve_hints_with_country_only() {
+        let hints = LibPostalService::resolve_hints(None, Some("de"));
+
+        assert!(!hints.is_empty());
+        assert_eq!(hints.language, None);
+        assert_eq!(hints.country.as_deref(), Some("DE"));
+    }
+
+    #[test]
+    fn resolve_hints_with_language_and_country() {
+        let hints = LibPostalService::resolve_hints(Some("en"), Some("us"));
+
+        assert_eq!(hints.language.as_deref(), Some("en"));
+        assert_eq!(hints.country.as_deref(), Some("US"));
+    }
+
+    #[test]
+    fn resolve_hints_drops_blank_hints() {
+        let hints = LibPostalService::resolve_hints(Some("  "), Some(""));
+
+        assert!(hints.is_empty());
+    }
 }
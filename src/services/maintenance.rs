@@ -0,0 +1,124 @@
+use std::sync::RwLock;
+
+use crate::models::MaintenanceStatus;
+
+/// Message returned while in maintenance mode when none was given
+pub const DEFAULT_MAINTENANCE_MESSAGE: &str = "The service is down for maintenance";
+
+/// Switch for turning API requests away while operators work on the service
+#[derive(Default)]
+pub struct MaintenanceMode {
+    status: RwLock<MaintenanceStatus>,
+}
+
+impl MaintenanceMode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start turning requests away with the given message
+    ///
+    /// Calling this again while enabled only replaces the message.
+    pub fn enable(&self, message: Option<String>) -> MaintenanceStatus {
+        let message = message
+            .map(|message| message.trim().to_string())
+            .filter(|message| !message.is_empty())
+            .unwrap_or_else(|| DEFAULT_MAINTENANCE_MESSAGE.to_string());
+
+        let mut status = self.status.write().unwrap_or_else(|e| e.into_inner());
+        if !status.enabled {
+            status.since = Some(chrono::Utc::now().to_rfc3339());
+        }
+        status.enabled = true;
+        status.message = Some(message);
+        status.clone()
+    }
+
+    /// Serve requests normally again
+    pub fn disable(&self) -> MaintenanceStatus {
+        let mut status = self.status.write().unwrap_or_else(|e| e.into_inner());
+        *status = MaintenanceStatus::default();
+        status.clone()
+    }
+
+    /// Current state
+    pub fn status(&self) -> MaintenanceStatus {
+        self.status
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// The message to return if requests are currently being turned away
+    pub fn active_message(&self) -> Option<String> {
+        let status = self.status.read().unwrap_or_else(|e| e.into_inner());
+        status
+            .enabled
+            .then(|| status.message.clone().unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_are_served_until_enabled() {
+        let maintenance = MaintenanceMode::new();
+
+        assert_eq!(maintenance.active_message(), None);
+        assert!(!maintenance.status().enabled);
+        assert_eq!(maintenance.status().since, None);
+    }
+
+    #[test]
+    fn enabling_sets_the_message_and_start_time() {
+        let maintenance = MaintenanceMode::new();
+
+        let status = maintenance.enable(Some("  Upgrading data  ".to_string()));
+        assert!(status.enabled);
+        assert_eq!(status.message.as_deref(), Some("Upgrading data"));
+        assert!(status.since.is_some());
+        assert_eq!(
+            maintenance.active_message().as_deref(),
+            Some("Upgrading data")
+        );
+    }
+
+    #[test]
+    fn blank_messages_fall_back_to_the_default() {
+        let maintenance = MaintenanceMode::new();
+
+        for message in [None, Some(" ".to_string())] {
+            maintenance.enable(message);
+            assert_eq!(
+                maintenance.active_message().as_deref(),
+                Some(DEFAULT_MAINTENANCE_MESSAGE)
+            );
+        }
+    }
+
+    #[test]
+    fn enabling_again_only_replaces_the_message() {
+        let maintenance = MaintenanceMode::new();
+
+        let first = maintenance.enable(Some("first".to_string()));
+        let second = maintenance.enable(Some("second".to_string()));
+        assert_eq!(second.since, first.since);
+        assert_eq!(second.message.as_deref(), Some("second"));
+    }
+
+    #[test]
+    fn disabling_clears_the_state() {
+        let maintenance = MaintenanceMode::new();
+        maintenance.enable(Some("down".to_string()));
+
+        let status = maintenance.disable();
+        assert!(!status.enabled);
+        assert_eq!((status.message, status.since), (None, None));
+        assert_eq!(maintenance.active_message(), None);
+
+        // A later enable starts a new maintenance window
+        assert!(maintenance.enable(None).since.is_some());
+    }
+}
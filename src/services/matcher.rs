@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+
+use crate::models::{AddressComponents, MatchVerdict};
+
+/// Components compared by their own normalized expansions rather than literally
+///
+/// This lets "main st" agree with "main street" and "apt 4" with "#4".
+pub const EXPANDED_FIELDS: [&str; 2] = ["road", "unit"];
+
+/// Components compared between addresses and their weight in the score
+const FIELD_WEIGHTS: [(&str, f64); 8] = [
+    ("house_number", 0.2),
+    ("road", 0.25),
+    ("unit", 0.1),
+    ("po_box", 0.1),
+    ("postcode", 0.15),
+    ("city", 0.1),
+    ("state", 0.05),
+    ("country", 0.05),
+];
+
+/// Components that tell distinct places apart on the same street
+const IDENTIFYING_FIELDS: [&str; 4] = ["house_number", "unit", "po_box", "postcode"];
+
+/// Minimum score for a "likely" verdict
+const LIKELY_SCORE: f64 = 0.8;
+
+/// Minimum score for a "possible" verdict
+const POSSIBLE_SCORE: f64 = 0.5;
+
+/// A parsed and normalized address ready for comparison
+pub struct MatchCandidate {
+    /// Parsed components
+    pub components: AddressComponents,
+    /// Expansions of the whole address
+    pub expansions: Vec<String>,
+    /// Expansions of each component listed in [`EXPANDED_FIELDS`]
+    pub field_expansions: HashMap<&'static str, Vec<String>>,
+}
+
+/// Outcome of comparing two addresses
+pub struct PairOutcome {
+    pub verdict: MatchVerdict,
+    pub score: f64,
+    pub agreed: Vec<String>,
+    pub conflicted: Vec<String>,
+    pub shared_expansion: bool,
+}
+
+/// Compare two addresses
+///
+/// Components present in both addresses either agree or conflict; the score is
+/// the weighted share that agree. A shared full-address expansion with no
+/// conflicts is an exact match. A conflict on an identifying component (house
+/// number, unit, PO box or postcode) rules out "likely".
+pub fn compare(a: &MatchCandidate, b: &MatchCandidate) -> PairOutcome {
+    let shared_expansion = a
+        .expansions
+        .iter()
+        .any(|expansion| b.expansions.contains(expansion));
+
+    let mut agreed = Vec::new();
+    let mut conflicted = Vec::new();
+    let mut agreed_weight = 0.0;
+    let mut compared_weight = 0.0;
+
+    for (field, weight) in FIELD_WEIGHTS {
+        let (Some(left), Some(right)) = (a.components.get(field), b.components.get(field)) else {
+            continue;
+        };
+
+        compared_weight += weight;
+        if field_agrees(field, left, right, a, b) {
+            agreed_weight += weight;
+            agreed.push(field.to_string());
+        } else {
+            conflicted.push(field.to_string());
+        }
+    }
+
+    let score = if compared_weight > 0.0 {
+        agreed_weight / compared_weight
+    } else if shared_expansion {
+        1.0
+    } else {
+        0.0
+    };
+
+    let identifying_conflict = conflicted
+        .iter()
+        .any(|field| IDENTIFYING_FIELDS.contains(&field.as_str()));
+
+    let verdict = if shared_expansion && conflicted.is_empty() {
+        MatchVerdict::Exact
+    } else if score >= LIKELY_SCORE && !identifying_conflict {
+        MatchVerdict::Likely
+    } else if score >= POSSIBLE_SCORE {
+        MatchVerdict::Possible
+    } else {
+        MatchVerdict::None
+    };
+
+    PairOutcome {
+        verdict,
+        score: (score * 1000.0).round() / 1000.0,
+        agreed,
+        conflicted,
+        shared_expansion,
+    }
+}
+
+/// Whether a component agrees between two addresses
+fn field_agrees(
+    field: &str,
+    left: &str,
+    right: &str,
+    a: &MatchCandidate,
+    b: &MatchCandidate,
+) -> bool {
+    let (left_key, right_key) = (comparable(left), comparable(right));
+    if left_key == right_key {
+        return true;
+    }
+
+    if field == "postcode" && postcode_extends(&left_key, &right_key) {
+        return true;
+    }
+
+    match (a.field_expansions.get(field), b.field_expansions.get(field)) {
+        (Some(left), Some(right)) => left.iter().any(|expansion| right.contains(expansion)),
+        _ => false,
+    }
+}
+
+/// Whether one postcode is the full form of the other
+///
+/// Only two cases count: a 5-digit ZIP code against its ZIP+4, and a UK outward
+/// code such as "sw1a" against the same outward code with an inward code such
+/// as "1aa". Any other shared prefix is a different postcode.
+fn postcode_extends(left: &str, right: &str) -> bool {
+    let (shorter, longer) = if left.len() <= right.len() {
+        (left, right)
+    } else {
+        (right, left)
+    };
+    let Some(extension) = longer.strip_prefix(shorter) else {
+        return false;
+    };
+
+    let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    let zip_plus_four = shorter.len() == 5 && extension.len() == 4 && is_digits(longer);
+
+    zip_plus_four || (is_uk_outward_code(shorter) && is_uk_inward_code(extension))
+}
+
+/// UK outward code: one or two letters, a digit, then an optional letter or digit
+fn is_uk_outward_code(code: &str) -> bool {
+    let bytes = code.as_bytes();
+    let letters = bytes.iter().take_while(|b| b.is_ascii_lowercase()).count();
+    match (letters, &bytes[letters..]) {
+        (1 | 2, [digit]) => digit.is_ascii_digit(),
+        (1 | 2, [digit, last]) => digit.is_ascii_digit() && last.is_ascii_alphanumeric(),
+        _ => false,
+    }
+}
+
+/// UK inward code: a digit followed by two letters
+fn is_uk_inward_code(code: &str) -> bool {
+    matches!(
+        code.as_bytes(),
+        [digit, first, second]
+            if digit.is_ascii_digit() && first.is_ascii_lowercase() && second.is_ascii_lowercase()
+    )
+}
+
+/// Lowercased letters and digits only, so "10001-1234" and "10001 1234" compare equal
+fn comparable(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(fields: &[(&str, &str)]) -> MatchCandidate {
+        let mut components = AddressComponents::default();
+        for (field, value) in fields {
+            let value = Some(value.to_string());
+            match *field {
+                "house_number" => components.house_number = value,
+                "road" => components.road = value,
+                "unit" => components.unit = value,
+                "postcode" => components.postcode = value,
+                "city" => components.city = value,
+                other => panic!("unexpected field {other}"),
+            }
+        }
+        MatchCandidate {
+            components,
+            expansions: Vec::new(),
+            field_expansions: HashMap::new(),
+        }
+    }
+
+    fn with_expansions(
+        mut candidate: MatchCandidate,
+        field: &'static str,
+        expansions: &[&str],
+    ) -> MatchCandidate {
+        candidate
+            .field_expansions
+            .insert(field, expansions.iter().map(|e| e.to_string()).collect());
+        candidate
+    }
+
+    fn postcodes_agree(left: &str, right: &str) -> bool {
+        let (a, b) = (candidate(&[]), candidate(&[]));
+        field_agrees("postcode", left, right, &a, &b)
+    }
+
+    #[test]
+    fn zip_codes_agree_with_their_zip_plus_four() {
+        assert!(postcodes_agree("10001", "10001-1234"));
+        assert!(postcodes_agree("10001 1234", "10001"));
+        assert!(!postcodes_agree("10001", "10002-1234"));
+        assert!(!postcodes_agree("1", "10001"));
+        assert!(!postcodes_agree("1000", "10001"));
+        assert!(!postcodes_agree("10001", "1000123"));
+    }
+
+    #[test]
+    fn uk_outward_codes_agree_with_full_postcodes() {
+        assert!(postcodes_agree("SW1A", "SW1A 1AA"));
+        assert!(postcodes_agree("m1", "M1 1AE"));
+        assert!(postcodes_agree("EC1A", "ec1a1bb"));
+        assert!(!postcodes_agree("SW1", "SW1A 1AA"));
+        assert!(!postcodes_agree("S", "SW1A 1AA"));
+        assert!(!postcodes_agree("SW1A 1", "SW1A 1AA"));
+        assert!(!postcodes_agree("SW1A", "SW1B 1AA"));
+    }
+
+    #[test]
+    fn values_compare_without_case_or_punctuation() {
+        let (a, b) = (candidate(&[]), candidate(&[]));
+        assert!(field_agrees("city", "New York", "new-york", &a, &b));
+        assert!(field_agrees("house_number", "12 B", "12b", &a, &b));
+        assert!(!field_agrees("city", "Brooklyn", "Queens", &a, &b));
+    }
+
+    #[test]
+    fn expanded_fields_agree_on_a_shared_expansion() {
+        let a = with_expansions(candidate(&[]), "road", &["main street"]);
+        let b = with_expansions(candidate(&[]), "road", &["main saint", "main street"]);
+        assert!(field_agrees("road", "main st", "main street", &a, &b));
+
+        // Expansions of one field say nothing about another
+        assert!(!field_agrees("city", "main st", "main street", &a, &b));
+
+        let c = with_expansions(candidate(&[]), "road", &["high street"]);
+        assert!(!field_agrees("road", "main st", "high st", &a, &c));
+    }
+
+    #[test]
+    fn identifying_conflicts_rule_out_likely() {
+        let a = candidate(&[
+            ("house_number", "12"),
+            ("road", "main street"),
+            ("unit", "4"),
+            ("city", "springfield"),
+            ("postcode", "12345"),
+        ]);
+        let b = candidate(&[
+            ("house_number", "12"),
+            ("road", "main street"),
+            ("unit", "5"),
+            ("city", "springfield"),
+            ("postcode", "12345"),
+        ]);
+
+        let outcome = compare(&a, &b);
+        assert_eq!(outcome.conflicted, ["unit"]);
+        assert_eq!(outcome.score, 0.875);
+        assert_eq!(outcome.verdict, MatchVerdict::Possible);
+    }
+
+    #[test]
+    fn shared_expansion_without_conflicts_is_exact() {
+        let mut a = candidate(&[("house_number", "12"), ("road", "main st")]);
+        let mut b = candidate(&[("house_number", "12")]);
+        a.expansions = vec!["12 main street".to_string()];
+        b.expansions = vec!["12 main street".to_string()];
+
+        let outcome = compare(&a, &b);
+        assert!(outcome.shared_expansion);
+        assert_eq!(outcome.agreed, ["house_number"]);
+        assert_eq!(outcome.verdict, MatchVerdict::Exact);
+
+        let outcome = compare(&candidate(&[]), &candidate(&[]));
+        assert_eq!((outcome.score, outcome.verdict), (0.0, MatchVerdict::None));
+    }
+}
@@ -0,0 +1,226 @@
+use prometheus::{
+    exponential_buckets, Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::{sync::LazyLock, time::Duration};
+
+use crate::models::QueueMetrics;
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// Prometheus metrics collected by the service
+pub struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_duration: HistogramVec,
+    errors: IntCounterVec,
+    queue_wait: HistogramVec,
+    libpostal_duration: HistogramVec,
+    expansions: HistogramVec,
+    workers: IntGauge,
+    workers_active: IntGauge,
+    queue_length: IntGauge,
+    queue_rejected: IntCounter,
+    cache_lookups: IntCounterVec,
+    cache_entries: IntGaugeVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("libpostal_api".to_string()), None)
+            .expect("valid metrics registry");
+
+        let http_requests = IntCounterVec::new(
+            Opts::new(
+                "http_requests_total",
+                "HTTP requests by route, method and status",
+            ),
+            &["route", "method", "status"],
+        )
+        .expect("valid metric");
+        let http_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "HTTP request latency by route and method",
+            )
+            .buckets(exponential_buckets(0.0005, 2.0, 16).expect("valid buckets")),
+            &["route", "method"],
+        )
+        .expect("valid metric");
+        let errors = IntCounterVec::new(
+            Opts::new("errors_total", "API errors by error code"),
+            &["code"],
+        )
+        .expect("valid metric");
+        let queue_wait = HistogramVec::new(
+            HistogramOpts::new(
+                "libpostal_queue_wait_seconds",
+                "Time spent waiting for a free libpostal worker",
+            )
+            .buckets(exponential_buckets(0.0001, 2.0, 16).expect("valid buckets")),
+            &["operation"],
+        )
+        .expect("valid metric");
+        let libpostal_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "libpostal_call_duration_seconds",
+                "Time spent inside libpostal by operation",
+            )
+            .buckets(exponential_buckets(0.0001, 2.0, 16).expect("valid buckets")),
+            &["operation"],
+        )
+        .expect("valid metric");
+        let expansions = HistogramVec::new(
+            HistogramOpts::new(
+                "normalize_expansions",
+                "Number of expansions returned per normalized address",
+            )
+            .buckets(vec![1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 128.0]),
+            &["level"],
+        )
+        .expect("valid metric");
+        let workers = IntGauge::new("libpostal_workers", "Number of libpostal workers")
+            .expect("valid metric");
+        let workers_active = IntGauge::new(
+            "libpostal_workers_active",
+            "libpostal workers currently running a job",
+        )
+        .expect("valid metric");
+        let queue_length = IntGauge::new(
+            "libpostal_queue_length",
+            "Jobs waiting for a free libpostal worker",
+        )
+        .expect("valid metric");
+        let queue_rejected = IntCounter::new(
+            "libpostal_queue_rejected_total",
+            "Jobs rejected because the worker queue was full",
+        )
+        .expect("valid metric");
+        let cache_lookups = IntCounterVec::new(
+            Opts::new(
+                "cache_lookups_total",
+                "Result cache lookups by operation and result",
+            ),
+            &["operation", "result"],
+        )
+        .expect("valid metric");
+        let cache_entries = IntGaugeVec::new(
+            Opts::new(
+                "cache_entries",
+                "Results currently held in the cache by operation",
+            ),
+            &["operation"],
+        )
+        .expect("valid metric");
+
+        for collector in [
+            Box::new(http_requests.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(http_duration.clone()),
+            Box::new(errors.clone()),
+            Box::new(queue_wait.clone()),
+            Box::new(libpostal_duration.clone()),
+            Box::new(expansions.clone()),
+            Box::new(workers.clone()),
+            Box::new(workers_active.clone()),
+            Box::new(queue_length.clone()),
+            Box::new(queue_rejected.clone()),
+            Box::new(cache_lookups.clone()),
+            Box::new(cache_entries.clone()),
+        ] {
+            registry.register(collector).expect("unique metric");
+        }
+
+        Self {
+            registry,
+            http_requests,
+            http_duration,
+            errors,
+            queue_wait,
+            libpostal_duration,
+            expansions,
+            workers,
+            workers_active,
+            queue_length,
+            queue_rejected,
+            cache_lookups,
+            cache_entries,
+        }
+    }
+
+    /// Get the process-wide metrics
+    pub fn global() -> &'static Metrics {
+        &METRICS
+    }
+
+    /// Record a completed HTTP request
+    pub fn record_request(&self, route: &str, method: &str, status: u16, elapsed: Duration) {
+        self.http_requests
+            .with_label_values(&[route, method, &status.to_string()])
+            .inc();
+        self.http_duration
+            .with_label_values(&[route, method])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Record an API error by its code
+    pub fn record_error(&self, code: &str) {
+        self.errors.with_label_values(&[code]).inc();
+    }
+
+    /// Record how long a job waited for a worker
+    pub fn record_queue_wait(&self, operation: &str, wait: Duration) {
+        self.queue_wait
+            .with_label_values(&[operation])
+            .observe(wait.as_secs_f64());
+    }
+
+    /// Record a job rejected because the worker queue was full
+    pub fn record_queue_rejected(&self) {
+        self.queue_rejected.inc();
+    }
+
+    /// Record how long a libpostal call took
+    pub fn record_libpostal_call(&self, operation: &str, elapsed: Duration) {
+        self.libpostal_duration
+            .with_label_values(&[operation])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Record the number of expansions produced by a normalize call
+    pub fn record_expansions(&self, level: &str, count: usize) {
+        self.expansions
+            .with_label_values(&[level])
+            .observe(count as f64);
+    }
+
+    /// Record a result cache lookup as a hit or a miss
+    pub fn record_cache_lookup(&self, operation: &str, hit: bool) {
+        let result = if hit { "hit" } else { "miss" };
+        self.cache_lookups
+            .with_label_values(&[operation, result])
+            .inc();
+    }
+
+    /// Record the number of results held in a cache
+    pub fn set_cache_entries(&self, operation: &str, entries: usize) {
+        self.cache_entries
+            .with_label_values(&[operation])
+            .set(entries as i64);
+    }
+
+    /// Render all metrics in the Prometheus text format
+    pub fn render(&self, queue: Option<&QueueMetrics>) -> String {
+        if let Some(queue) = queue {
+            self.workers.set(queue.workers as i64);
+            self.workers_active.set(queue.active as i64);
+            self.queue_length.set(queue.queued as i64);
+        }
+
+        let mut buffer = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            tracing::error!("Failed to encode metrics: {}", e);
+        }
+
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
@@ -1,3 +1,17 @@
+pub mod cache;
+pub mod canary;
+pub mod engine;
+#[cfg(test)]
+pub mod fake;
+pub mod formatter;
+pub mod health;
+pub mod jobs;
 pub mod libpostal;
+pub mod maintenance;
+pub mod matcher;
+pub mod metrics;
+pub mod reload;
+pub mod tokens;
+pub mod worker_pool;
 
 pub use libpostal::LibPostalService;
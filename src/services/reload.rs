@@ -0,0 +1,280 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use libpostal_rs::data::DataManager;
+use tracing::{error, info};
+
+use crate::{
+    error::{ApiError, ApiResult},
+    models::{CanaryReport, ReloadState, ReloadStatus},
+    services::{canary::failure_summary, LibPostalService},
+};
+
+/// Longest a verification process may take to load the model and parse the canaries
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Checks data for a reload in the background, one directory at a time
+///
+/// The data is loaded and checked with the canary parses in a separate
+/// process, so the model in service keeps answering requests at full speed.
+/// A directory that passes is not put in service here: libpostal can only
+/// replace its process-wide model by tearing it down first, so new data goes
+/// into service by restarting the server on it (see [`LibPostalService`]).
+#[derive(Default)]
+pub struct ModelReloader {
+    latest: Mutex<Option<ReloadStatus>>,
+}
+
+impl ModelReloader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start checking `data_dir`, or the directory of the model in service
+    ///
+    /// Returns the initial status; progress is reported by [`Self::latest`].
+    pub fn start(
+        self: &Arc<Self>,
+        data_dir: Option<PathBuf>,
+        verify_integrity: bool,
+    ) -> ApiResult<ReloadStatus> {
+        let previous_data_dir = LibPostalService::global()?.data_dir().to_path_buf();
+        let data_dir = data_dir.unwrap_or_else(|| previous_data_dir.clone());
+
+        if !DataManager::with_data_dir(&data_dir).is_data_available() {
+            return Err(ApiError::InvalidInput(format!(
+                "No LibPostal data found in {}",
+                data_dir.display()
+            )));
+        }
+
+        let status = self.begin(&data_dir, &previous_data_dir)?;
+
+        let reloader = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = reloader.run(data_dir, verify_integrity).await {
+                error!(error = %e, "LibPostal model reload failed");
+                reloader.fail(&e);
+            }
+        });
+
+        Ok(status)
+    }
+
+    /// Record a new reload as verifying, unless one is already in progress
+    fn begin(&self, data_dir: &Path, previous_data_dir: &Path) -> ApiResult<ReloadStatus> {
+        let mut latest = self.latest.lock().unwrap_or_else(|e| e.into_inner());
+        if latest
+            .as_ref()
+            .is_some_and(|status| status.state == ReloadState::Verifying)
+        {
+            return Err(ApiError::Conflict(
+                "A reload is already in progress".to_string(),
+            ));
+        }
+
+        let status = ReloadStatus {
+            state: ReloadState::Verifying,
+            data_dir: data_dir.display().to_string(),
+            previous_data_dir: previous_data_dir.display().to_string(),
+            started_at: chrono::Utc::now().to_rfc3339(),
+            finished_at: None,
+            canary: None,
+            error: None,
+        };
+        *latest = Some(status.clone());
+        Ok(status)
+    }
+
+    /// Record that the reload in progress failed
+    fn fail(&self, error: &ApiError) {
+        self.update(|status| {
+            status.state = ReloadState::Failed;
+            status.finished_at = Some(chrono::Utc::now().to_rfc3339());
+            // Only admins see the status, so the full error is reported
+            status.error = Some(error.to_string());
+        });
+    }
+
+    /// Record that the data in progress passed every check
+    fn complete(&self, canary: CanaryReport) {
+        self.update(|status| {
+            status.state = ReloadState::Verified;
+            status.finished_at = Some(chrono::Utc::now().to_rfc3339());
+            status.canary = Some(canary);
+        });
+    }
+
+    /// Status of the most recent reload, if one was started
+    pub fn latest(&self) -> Option<ReloadStatus> {
+        self.latest
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    async fn run(&self, data_dir: PathBuf, verify_integrity: bool) -> ApiResult<()> {
+        info!(data_dir = %data_dir.display(), "Verifying LibPostal data for reload");
+
+        let data_integrity =
+            LibPostalService::check_data_integrity(&data_dir, verify_integrity).await;
+        if data_integrity.status == "failed" {
+            return Err(ApiError::InvalidInput(format!(
+                "LibPostal data in {} failed the integrity check: {}",
+                data_dir.display(),
+                data_integrity.message.as_deref().unwrap_or("unknown error")
+            )));
+        }
+
+        let canary = verify_in_child_process(&data_dir).await?;
+        if !canary.passed {
+            let summary = failure_summary(&canary);
+            self.update(|status| status.canary = Some(canary));
+            return Err(ApiError::InvalidInput(format!(
+                "The model in {} failed the canary parses: {summary}",
+                data_dir.display()
+            )));
+        }
+
+        info!(data_dir = %data_dir.display(), "LibPostal data verified; restart the server on it to put it in service");
+        self.complete(canary);
+        Ok(())
+    }
+
+    fn update(&self, change: impl FnOnce(&mut ReloadStatus)) {
+        if let Some(status) = self
+            .latest
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_mut()
+        {
+            change(status);
+        }
+    }
+}
+
+/// Load the model from `data_dir` in a child process and parse the canaries there
+///
+/// libpostal holds one model per process, so this is the only way to try a new
+/// model without disturbing the one serving requests. The child is this same
+/// binary run with `--verify-data`; it prints a [`CanaryReport`] as JSON.
+async fn verify_in_child_process(data_dir: &Path) -> ApiResult<CanaryReport> {
+    let exe = std::env::current_exe()
+        .map_err(|e| ApiError::Internal(format!("Unable to locate the server binary: {e}")))?;
+
+    let child = tokio::process::Command::new(exe)
+        .arg("--verify-data")
+        .arg(data_dir)
+        .env("LIBPOSTAL_DATA_DIR", data_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| {
+            ApiError::Internal(format!("Unable to start the verification process: {e}"))
+        })?;
+
+    let output = tokio::time::timeout(VERIFY_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| {
+            ApiError::ServiceUnavailable(format!(
+                "Verifying {} took longer than {} seconds",
+                data_dir.display(),
+                VERIFY_TIMEOUT.as_secs()
+            ))
+        })?
+        .map_err(|e| ApiError::Internal(format!("Verification process failed: {e}")))?;
+
+    // A report is printed whether or not the canaries passed
+    serde_json::from_slice(&output.stdout).map_err(|_| {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let detail = stderr
+            .lines()
+            .map(str::trim)
+            .rfind(|line| !line.is_empty())
+            .unwrap_or("no output")
+            .to_string();
+        ApiError::ServiceUnavailable(format!(
+            "Unable to load LibPostal data from {} ({}): {detail}",
+            data_dir.display(),
+            output.status
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn begin(reloader: &ModelReloader) -> ApiResult<ReloadStatus> {
+        reloader.begin(Path::new("/data/new"), Path::new("/data/current"))
+    }
+
+    fn state(reloader: &ModelReloader) -> Option<ReloadState> {
+        reloader.latest().map(|status| status.state)
+    }
+
+    #[test]
+    fn only_one_reload_runs_at_a_time() {
+        let reloader = ModelReloader::new();
+        assert_eq!(state(&reloader), None);
+
+        let status = begin(&reloader).unwrap();
+        assert_eq!(status.state, ReloadState::Verifying);
+        assert_eq!(status.data_dir, "/data/new");
+        assert_eq!(status.previous_data_dir, "/data/current");
+        assert!(matches!(begin(&reloader), Err(ApiError::Conflict(_))));
+        assert_eq!(state(&reloader), Some(ReloadState::Verifying));
+    }
+
+    #[test]
+    fn failed_reloads_keep_the_error_and_allow_another() {
+        let reloader = ModelReloader::new();
+        begin(&reloader).unwrap();
+
+        reloader.fail(&ApiError::InvalidInput("canaries failed".to_string()));
+        let failed = reloader.latest().unwrap();
+        assert_eq!(failed.state, ReloadState::Failed);
+        assert!(failed.finished_at.is_some());
+        assert_eq!(
+            failed.error.as_deref(),
+            Some("Invalid input: canaries failed")
+        );
+
+        let restarted = begin(&reloader).unwrap();
+        assert_eq!(restarted.state, ReloadState::Verifying);
+        assert_eq!((restarted.finished_at, restarted.error), (None, None));
+    }
+
+    #[test]
+    fn verified_data_reports_the_canaries() {
+        let reloader = ModelReloader::new();
+        begin(&reloader).unwrap();
+
+        let canary = CanaryReport {
+            passed: true,
+            checks: Vec::new(),
+        };
+        reloader.complete(canary);
+
+        let verified = reloader.latest().unwrap();
+        assert_eq!(verified.state, ReloadState::Verified);
+        assert!(verified.finished_at.is_some());
+        assert!(verified.canary.is_some_and(|canary| canary.passed));
+        assert_eq!(verified.error, None);
+
+        assert!(begin(&reloader).is_ok());
+    }
+
+    #[test]
+    fn updates_without_a_reload_are_ignored() {
+        let reloader = ModelReloader::new();
+        reloader.fail(&ApiError::Internal("late".to_string()));
+        assert_eq!(state(&reloader), None);
+    }
+}
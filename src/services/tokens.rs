@@ -0,0 +1,238 @@
+use libpostal_rs::ParsedAddress;
+
+use crate::models::AddressToken;
+
+/// Labelled spans of a parsed address, ordered as they appear in the input
+///
+/// libpostal-rs only exposes the parsed components by label, so each value is
+/// located in the original string again. Matching ignores case and allows any
+/// run of whitespace or punctuation between words. Longer values are placed
+/// first so a short value such as a house number cannot take text belonging to
+/// a longer one. Values that cannot be found are listed last without offsets.
+pub fn address_tokens(original: &str, parsed: &ParsedAddress) -> Vec<AddressToken> {
+    let input: Vec<char> = original.chars().map(fold_char).collect();
+    let mut claimed = vec![false; input.len()];
+
+    let mut located = Vec::new();
+    let mut unlocated = Vec::new();
+
+    let mut components: Vec<(&str, &str, Vec<Vec<char>>)> = labelled_components(parsed)
+        .into_iter()
+        .map(|(label, value)| {
+            let words = value
+                .split_whitespace()
+                .map(|word| word.chars().map(fold_char).collect())
+                .collect();
+            (label, value, words)
+        })
+        .collect();
+    components
+        .sort_by_key(|(_, _, words)| std::cmp::Reverse(words.iter().map(Vec::len).sum::<usize>()));
+
+    for (label, value, words) in components {
+        match find_span(&input, &claimed, &words) {
+            Some((start, end)) => {
+                claimed[start..end].iter_mut().for_each(|c| *c = true);
+                located.push(AddressToken {
+                    label: label.to_string(),
+                    value: value.to_string(),
+                    start: Some(start),
+                    end: Some(end),
+                });
+            }
+            None => unlocated.push(AddressToken {
+                label: label.to_string(),
+                value: value.to_string(),
+                start: None,
+                end: None,
+            }),
+        }
+    }
+
+    located.sort_by_key(|token| token.start);
+    located.extend(unlocated);
+    located
+}
+
+/// Components of a parsed address paired with libpostal's label names
+fn labelled_components(parsed: &ParsedAddress) -> Vec<(&'static str, &str)> {
+    let named = [
+        ("house_number", &parsed.house_number),
+        ("road", &parsed.road),
+        ("unit", &parsed.unit),
+        ("level", &parsed.level),
+        ("staircase", &parsed.staircase),
+        ("entrance", &parsed.entrance),
+        ("po_box", &parsed.po_box),
+        ("postcode", &parsed.postcode),
+        ("suburb", &parsed.suburb),
+        ("city", &parsed.city),
+        ("city_district", &parsed.city_district),
+        ("island", &parsed.island),
+        ("state", &parsed.state),
+        ("state_district", &parsed.state_district),
+        ("country_region", &parsed.country_region),
+        ("country", &parsed.country),
+        ("world_region", &parsed.world_region),
+        ("category", &parsed.category),
+        ("near", &parsed.near),
+        ("toponym", &parsed.toponym),
+    ];
+
+    named
+        .into_iter()
+        .filter_map(|(label, value)| value.as_deref().map(|value| (label, value)))
+        .chain(parsed.other.iter().map(|value| ("other", value.as_str())))
+        .collect()
+}
+
+/// First unclaimed span of the input matching the words, as character offsets
+fn find_span(input: &[char], claimed: &[bool], words: &[Vec<char>]) -> Option<(usize, usize)> {
+    if words.is_empty() {
+        return None;
+    }
+
+    (0..input.len())
+        .filter(|&start| start == 0 || !input[start - 1].is_alphanumeric())
+        .find_map(|start| {
+            let end = match_words(input, start, words)?;
+            let at_boundary = end == input.len() || !input[end].is_alphanumeric();
+            let free = !claimed[start..end].contains(&true);
+            (at_boundary && free).then_some((start, end))
+        })
+}
+
+/// Match the words at `start`, returning the offset just past the last one
+fn match_words(input: &[char], start: usize, words: &[Vec<char>]) -> Option<usize> {
+    let mut pos = start;
+
+    for (i, word) in words.iter().enumerate() {
+        if i > 0 {
+            while pos < input.len() && !input[pos].is_alphanumeric() && input[pos] != word[0] {
+                pos += 1;
+            }
+        }
+
+        if !input.get(pos..pos + word.len())?.eq(word.as_slice()) {
+            return None;
+        }
+        pos += word.len();
+    }
+
+    Some(pos)
+}
+
+/// Case-fold a character for matching, keeping one character per input character
+fn fold_char(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans(
+        original: &str,
+        parsed: &ParsedAddress,
+    ) -> Vec<(String, Option<usize>, Option<usize>)> {
+        address_tokens(original, parsed)
+            .into_iter()
+            .map(|token| (token.label, token.start, token.end))
+            .collect()
+    }
+
+    fn span(label: &str, start: usize, end: usize) -> (String, Option<usize>, Option<usize>) {
+        (label.to_string(), Some(start), Some(end))
+    }
+
+    #[test]
+    fn repeated_words_are_given_to_one_component_each() {
+        let parsed = ParsedAddress {
+            house_number: Some("10".to_string()),
+            road: Some("main st".to_string()),
+            unit: Some("10".to_string()),
+            city: Some("main city".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            spans("10 Main St Flat 10, Main City", &parsed),
+            vec![
+                span("house_number", 0, 2),
+                span("road", 3, 10),
+                span("unit", 16, 18),
+                span("city", 20, 29),
+            ]
+        );
+    }
+
+    #[test]
+    fn short_values_do_not_match_inside_words() {
+        let parsed = ParsedAddress {
+            house_number: Some("1".to_string()),
+            road: Some("1st ave".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            spans("11 1st Ave 1", &parsed),
+            vec![span("road", 3, 10), span("house_number", 11, 12)]
+        );
+    }
+
+    #[test]
+    fn matching_folds_case_including_accented_letters() {
+        let parsed = ParsedAddress {
+            road: Some("königstraße".to_string()),
+            house_number: Some("5".to_string()),
+            city: Some("münchen".to_string()),
+            ..Default::default()
+        };
+
+        // Offsets count characters, not bytes
+        assert_eq!(
+            spans("KÖNIGSTRAßE 5, MÜNCHEN", &parsed),
+            vec![
+                span("road", 0, 11),
+                span("house_number", 12, 13),
+                span("city", 15, 22),
+            ]
+        );
+    }
+
+    #[test]
+    fn punctuation_and_whitespace_runs_separate_words() {
+        let parsed = ParsedAddress {
+            road: Some("franklin ave".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(spans("Franklin-Ave.", &parsed), vec![span("road", 0, 12)]);
+        assert_eq!(spans("Franklin \t Ave", &parsed), vec![span("road", 0, 14)]);
+    }
+
+    #[test]
+    fn unmatched_components_are_listed_last_without_offsets() {
+        let parsed = ParsedAddress {
+            house_number: Some("781".to_string()),
+            road: Some("franklin avenue".to_string()),
+            other: vec!["ny".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            spans("781 Franklin Ave, NY", &parsed),
+            vec![
+                span("house_number", 0, 3),
+                span("other", 18, 20),
+                ("road".to_string(), None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_values_are_not_located() {
+        let words: Vec<Vec<char>> = Vec::new();
+        assert_eq!(find_span(&['a'], &[false], &words), None);
+    }
+}
@@ -0,0 +1,235 @@
+use crate::{
+    config::WorkerConfig,
+    error::{ApiError, ApiResult},
+    models::QueueMetrics,
+    services::metrics::Metrics,
+};
+use std::{
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+use tokio::sync::Semaphore;
+
+/// Bounded pool of blocking workers for CPU-heavy libpostal calls
+///
+/// At most `workers` jobs run at once on tokio's blocking thread pool. Further
+/// jobs wait in a queue of at most `queue_depth` entries; anything beyond that
+/// is rejected immediately so callers can back off.
+pub struct WorkerPool {
+    permits: Arc<Semaphore>,
+    workers: usize,
+    queue_depth: usize,
+    queued: AtomicUsize,
+    active: AtomicUsize,
+    completed: AtomicU64,
+    rejected: AtomicU64,
+    total_wait_us: AtomicU64,
+}
+
+impl WorkerPool {
+    /// Create a pool sized from the worker settings
+    pub fn new(config: &WorkerConfig) -> Self {
+        let workers = config.resolved_workers();
+
+        Self {
+            permits: Arc::new(Semaphore::new(workers)),
+            workers,
+            queue_depth: config.queue_depth,
+            queued: AtomicUsize::new(0),
+            active: AtomicUsize::new(0),
+            completed: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+            total_wait_us: AtomicU64::new(0),
+        }
+    }
+
+    /// Run a blocking job on a worker, waiting in the queue if all workers are busy
+    ///
+    /// `operation` labels the job in the queue wait and call duration metrics.
+    pub async fn run<T, F>(&self, operation: &'static str, job: F) -> ApiResult<T>
+    where
+        F: FnOnce() -> ApiResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let wait_start = Instant::now();
+
+        let permit = match self.permits.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                let _slot = self.reserve_queue_slot()?;
+                self.permits
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .map_err(|_| ApiError::Internal("Worker pool closed".to_string()))?
+            }
+        };
+
+        let wait = wait_start.elapsed();
+        self.total_wait_us
+            .fetch_add(wait.as_micros() as u64, Ordering::Relaxed);
+        Metrics::global().record_queue_wait(operation, wait);
+        let _active = ActiveJob::start(self);
+
+        tokio::task::spawn_blocking(move || {
+            let call_start = Instant::now();
+            let result = job();
+            Metrics::global().record_libpostal_call(operation, call_start.elapsed());
+            drop(permit);
+            result
+        })
+        .await
+        .map_err(|e| ApiError::Internal(format!("Worker task failed: {e}")))?
+    }
+
+    /// Snapshot of the pool's current load and counters
+    pub fn metrics(&self) -> QueueMetrics {
+        let completed = self.completed.load(Ordering::Relaxed);
+        let total_wait_us = self.total_wait_us.load(Ordering::Relaxed);
+
+        QueueMetrics {
+            workers: self.workers,
+            active: self.active.load(Ordering::Relaxed),
+            queued: self.queued.load(Ordering::Relaxed),
+            queue_depth: self.queue_depth,
+            completed,
+            rejected: self.rejected.load(Ordering::Relaxed),
+            average_wait_ms: if completed == 0 {
+                0.0
+            } else {
+                total_wait_us as f64 / completed as f64 / 1000.0
+            },
+        }
+    }
+
+    fn reserve_queue_slot(&self) -> ApiResult<QueueSlot<'_>> {
+        let position = self.queued.fetch_add(1, Ordering::AcqRel);
+        if position >= self.queue_depth {
+            self.queued.fetch_sub(1, Ordering::AcqRel);
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+            Metrics::global().record_queue_rejected();
+            return Err(ApiError::QueueFull);
+        }
+
+        Ok(QueueSlot { pool: self })
+    }
+}
+
+/// Place in the wait queue, released when the job starts or the caller goes away
+struct QueueSlot<'a> {
+    pool: &'a WorkerPool,
+}
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        self.pool.queued.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Job running on a worker, counted as completed once the caller is done with it
+struct ActiveJob<'a> {
+    pool: &'a WorkerPool,
+}
+
+impl<'a> ActiveJob<'a> {
+    fn start(pool: &'a WorkerPool) -> Self {
+        pool.active.fetch_add(1, Ordering::Relaxed);
+        Self { pool }
+    }
+}
+
+impl Drop for ActiveJob<'_> {
+    fn drop(&mut self) {
+        self.pool.active.fetch_sub(1, Ordering::Relaxed);
+        self.pool.completed.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, time::Duration};
+
+    use super::*;
+
+    fn pool(threads: usize, queue_depth: usize) -> Arc<WorkerPool> {
+        Arc::new(WorkerPool::new(&WorkerConfig {
+            threads,
+            queue_depth,
+        }))
+    }
+
+    /// Start a job that holds its worker until the returned sender is used
+    fn blocked_job(
+        pool: &Arc<WorkerPool>,
+    ) -> (mpsc::Sender<()>, tokio::task::JoinHandle<ApiResult<u32>>) {
+        let (release, wait) = mpsc::channel::<()>();
+        let pool = pool.clone();
+        let handle = tokio::spawn(async move {
+            pool.run("test", move || {
+                wait.recv().ok();
+                Ok(1)
+            })
+            .await
+        });
+        (release, handle)
+    }
+
+    async fn wait_until(pool: &WorkerPool, condition: impl Fn(&QueueMetrics) -> bool) {
+        for _ in 0..400 {
+            if condition(&pool.metrics()) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!(
+            "pool never reached the expected state: {:?}",
+            pool.metrics()
+        );
+    }
+
+    #[tokio::test]
+    async fn jobs_beyond_the_queue_depth_are_rejected() {
+        let pool = pool(1, 1);
+        let (release, running) = blocked_job(&pool);
+        wait_until(&pool, |m| m.active == 1).await;
+
+        let queued = {
+            let pool = pool.clone();
+            tokio::spawn(async move { pool.run("test", || Ok(2)).await })
+        };
+        wait_until(&pool, |m| m.queued == 1).await;
+
+        let rejected = pool.run("test", || Ok(3)).await;
+        assert!(matches!(rejected, Err(ApiError::QueueFull)));
+        assert_eq!(pool.metrics().rejected, 1);
+
+        release.send(()).unwrap();
+        assert_eq!(running.await.unwrap().unwrap(), 1);
+        assert_eq!(queued.await.unwrap().unwrap(), 2);
+
+        let metrics = pool.metrics();
+        assert_eq!(
+            (metrics.active, metrics.queued, metrics.completed),
+            (0, 0, 2)
+        );
+    }
+
+    #[tokio::test]
+    async fn a_zero_depth_queue_only_takes_free_workers() {
+        let pool = pool(1, 0);
+        assert_eq!(pool.run("test", || Ok(1)).await.unwrap(), 1);
+
+        let (release, running) = blocked_job(&pool);
+        wait_until(&pool, |m| m.active == 1).await;
+        assert!(matches!(
+            pool.run("test", || Ok(2)).await,
+            Err(ApiError::QueueFull)
+        ));
+
+        release.send(()).unwrap();
+        running.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,21 @@
+//! Small helpers shared by the service, middleware and error layers
+
+use std::time::Duration;
+
+/// Round a duration up to whole seconds, as used by `Retry-After` and `X-RateLimit-Reset`
+pub fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ceil_secs_rounds_partial_seconds_up() {
+        assert_eq!(ceil_secs(Duration::ZERO), 0);
+        assert_eq!(ceil_secs(Duration::from_secs(2)), 2);
+        assert_eq!(ceil_secs(Duration::from_millis(2001)), 3);
+        assert_eq!(ceil_secs(Duration::from_nanos(1)), 1);
+    }
+}